@@ -0,0 +1,5 @@
+mod weak_key_map;
+mod weak_set;
+
+pub use weak_key_map::WeakKeyMap;
+pub use weak_set::WeakSet;
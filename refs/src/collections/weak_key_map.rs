@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use crate::{Addr, Stamp, Weak};
+
+/// Map keyed by weak reference identity.
+///
+/// Values whose key was freed are never returned and are dropped
+/// from the map lazily on the next mutable access.
+pub struct WeakKeyMap<K: ?Sized, V> {
+    map: HashMap<(Addr, Stamp), (Weak<K>, V)>,
+}
+
+impl<K: ?Sized, V> WeakKeyMap<K, V> {
+    pub fn new() -> Self {
+        Self { map: HashMap::new() }
+    }
+
+    /// Returns previous value for this key. Null keys are ignored.
+    pub fn insert(&mut self, key: Weak<K>, val: V) -> Option<V> {
+        self.purge();
+
+        if key.is_null() {
+            return None;
+        }
+
+        self.map.insert(key.identity(), (key, val)).map(|(_, val)| val)
+    }
+
+    pub fn remove(&mut self, key: &Weak<K>) -> Option<V> {
+        self.purge();
+        self.map.remove(&key.identity()).map(|(_, val)| val)
+    }
+
+    pub fn get(&self, key: &Weak<K>) -> Option<&V> {
+        if key.is_null() {
+            return None;
+        }
+        self.map.get(&key.identity()).map(|(_, val)| val)
+    }
+
+    pub fn get_mut(&mut self, key: &Weak<K>) -> Option<&mut V> {
+        self.purge();
+        self.map.get_mut(&key.identity()).map(|(_, val)| val)
+    }
+
+    pub fn get_or_insert_with(&mut self, key: Weak<K>, make: impl FnOnce() -> V) -> Option<&mut V> {
+        self.purge();
+
+        if key.is_null() {
+            return None;
+        }
+
+        Some(&mut self.map.entry(key.identity()).or_insert_with(|| (key, make())).1)
+    }
+
+    pub fn contains_key(&self, key: &Weak<K>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Number of entries with live keys.
+    pub fn len(&self) -> usize {
+        self.map.values().filter(|(key, _)| key.is_ok()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Removes entries with freed keys.
+    pub fn purge(&mut self) {
+        self.map.retain(|_, (key, _)| key.is_ok());
+    }
+
+    pub fn iter(&mut self) -> impl Iterator<Item = (&K, &V)> {
+        self.purge();
+        self.map.values().filter_map(|(key, val)| Some((key.get()?, val)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.purge();
+        self.map.values_mut().filter_map(|(key, val)| Some((key.get()?, val)))
+    }
+
+    pub fn keys(&mut self) -> impl Iterator<Item = Weak<K>> + '_ {
+        self.purge();
+        self.map.values().map(|(key, _)| *key).filter(Weak::is_ok)
+    }
+
+    pub fn values(&mut self) -> impl Iterator<Item = &V> {
+        self.purge();
+        self.map.values().filter(|(key, _)| key.is_ok()).map(|(_, val)| val)
+    }
+}
+
+impl<K: ?Sized, V> Default for WeakKeyMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::{Own, Weak, collections::WeakKeyMap};
+
+    #[test]
+    #[serial]
+    fn weak_key_map() {
+        set_current_thread_as_main();
+
+        struct NonHash {
+            a: u8,
+        }
+
+        let a = Own::new(NonHash { a: 1 });
+        let b = Own::new(NonHash { a: 2 });
+
+        let mut map = WeakKeyMap::new();
+
+        assert_eq!(map.insert(a.weak(), "a"), None);
+        assert_eq!(map.insert(a.weak(), "aa"), Some("a"));
+        assert_eq!(map.insert(b.weak(), "b"), None);
+        assert_eq!(map.insert(Weak::default(), "null"), None);
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&a.weak()), Some(&"aa"));
+
+        *map.get_mut(&b.weak()).unwrap() = "bb";
+
+        let mut pairs: Vec<_> = map.iter().map(|(key, val)| (key.a, *val)).collect();
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![(1, "aa"), (2, "bb")]);
+
+        assert_eq!(map.remove(&a.weak()), Some("aa"));
+        assert!(!map.contains_key(&a.weak()));
+    }
+
+    #[test]
+    #[serial]
+    fn weak_key_map_skips_freed() {
+        set_current_thread_as_main();
+
+        let a = Own::new(1);
+        let b = Own::new(2);
+
+        let mut map = WeakKeyMap::new();
+        map.insert(a.weak(), 10);
+        map.insert(b.weak(), 20);
+
+        let a_weak = a.weak();
+        drop(a);
+
+        assert_eq!(map.get(&a_weak), None);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), vec![20]);
+        assert_eq!(map.keys().count(), 1);
+
+        assert_eq!(*map.get_or_insert_with(b.weak(), || 0).unwrap(), 20);
+        assert_eq!(map.get_or_insert_with(a_weak, || 0), None);
+
+        drop(b);
+
+        assert!(map.is_empty());
+        assert_eq!(map.iter().count(), 0);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::Weak;
+
+/// Set of weak references compared by pointer identity.
+///
+/// Entries whose `Own` was freed are skipped by lookups and dropped
+/// from the set lazily on the next mutable access.
+pub struct WeakSet<T: ?Sized> {
+    items: Vec<Weak<T>>,
+}
+
+impl<T: ?Sized> WeakSet<T> {
+    pub const fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// Returns `false` if the weak is null or already in the set.
+    pub fn insert(&mut self, weak: Weak<T>) -> bool {
+        self.purge();
+
+        if weak.is_null() || self.position(&weak).is_some() {
+            return false;
+        }
+
+        self.items.push(weak);
+        true
+    }
+
+    pub fn remove(&mut self, weak: &Weak<T>) -> bool {
+        self.purge();
+
+        let Some(index) = self.position(weak) else {
+            return false;
+        };
+
+        self.items.remove(index);
+        true
+    }
+
+    pub fn contains(&self, weak: &Weak<T>) -> bool {
+        weak.is_ok() && self.position(weak).is_some()
+    }
+
+    /// Number of live entries.
+    pub fn len(&self) -> usize {
+        self.items.iter().filter(|weak| weak.is_ok()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Removes entries pointing to freed objects.
+    pub fn purge(&mut self) {
+        self.items.retain(Weak::is_ok);
+    }
+
+    pub fn weaks(&mut self) -> impl Iterator<Item = Weak<T>> + '_ {
+        self.purge();
+        self.items.iter().copied().filter(Weak::is_ok)
+    }
+
+    pub fn iter(&mut self) -> impl Iterator<Item = &T> {
+        self.purge();
+        self.items.iter().filter_map(Weak::get)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.purge();
+        self.items.iter_mut().filter_map(Weak::get_mut)
+    }
+
+    fn position(&self, weak: &Weak<T>) -> Option<usize> {
+        self.items.iter().position(|item| item.identity() == weak.identity())
+    }
+}
+
+impl<T: ?Sized> Default for WeakSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> FromIterator<Weak<T>> for WeakSet<T> {
+    fn from_iter<I: IntoIterator<Item = Weak<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for weak in iter {
+            set.insert(weak);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::{Own, Weak, collections::WeakSet};
+
+    #[test]
+    #[serial]
+    fn weak_set_insert_remove() {
+        set_current_thread_as_main();
+
+        let a = Own::new(1);
+        let b = Own::new(2);
+
+        let mut set = WeakSet::new();
+
+        assert!(set.insert(a.weak()));
+        assert!(!set.insert(a.weak()));
+        assert!(set.insert(b.weak()));
+        assert!(!set.insert(Weak::default()));
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&a.weak()));
+
+        assert!(set.remove(&a.weak()));
+        assert!(!set.remove(&a.weak()));
+        assert!(!set.contains(&a.weak()));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn weak_set_skips_freed() {
+        set_current_thread_as_main();
+
+        let a = Own::new(1);
+        let b = Own::new(2);
+        let c = Own::new(3);
+
+        let mut set: WeakSet<i32> = [a.weak(), b.weak(), c.weak()].into_iter().collect();
+
+        let b_weak = b.weak();
+        drop(b);
+
+        assert_eq!(set.len(), 2);
+        assert!(!set.contains(&b_weak));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![1, 3]);
+
+        for val in set.iter_mut() {
+            *val *= 10;
+        }
+
+        assert_eq!(*a, 10);
+        assert_eq!(*c, 30);
+
+        drop(a);
+        drop(c);
+
+        assert!(set.is_empty());
+        assert_eq!(set.weaks().count(), 0);
+    }
+
+    #[test]
+    #[serial]
+    fn weak_set_unsized() {
+        set_current_thread_as_main();
+
+        trait Trait {
+            fn val(&self) -> i32;
+        }
+
+        struct Impl(i32);
+
+        impl Trait for Impl {
+            fn val(&self) -> i32 {
+                self.0
+            }
+        }
+
+        let a: Own<dyn Trait> = Own::new(Impl(5));
+
+        let mut set = WeakSet::<dyn Trait>::default();
+        set.insert(a.weak());
+
+        assert_eq!(set.iter().map(Trait::val).sum::<i32>(), 5);
+    }
+}
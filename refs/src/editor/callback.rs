@@ -1,3 +1,39 @@
+use std::fmt::{Display, Formatter};
+
+/// Identifies edited field. Generated by `#[derive(Edit)]` as
+/// `FIELD_<NAME>` constants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FieldId(&'static str);
+
+impl FieldId {
+    /// Used when `Editor` is created without field information.
+    pub const UNKNOWN: FieldId = FieldId::new("");
+
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Display for FieldId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 pub trait EditedCallback {
-    fn edited(&mut self);
+    fn edited(&mut self) {}
+
+    /// Called by `Editor` when it is dropped. Calls `edited` by default.
+    ///
+    /// For nested editors callbacks are called from the innermost object
+    /// outwards: `obj.edit_a().edit_b()` calls `A::edited_field(b)` before
+    /// `Obj::edited_field(a)`.
+    fn edited_field(&mut self, field: FieldId) {
+        let _ = field;
+        self.edited();
+    }
 }
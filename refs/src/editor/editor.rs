@@ -1,41 +1,113 @@
 use std::{
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    ptr::NonNull,
 };
 
-use crate::{Rglica, editor::EditedCallback};
+use crate::{
+    editor::{
+        EditedCallback, FieldId, UndoStack,
+        transaction::{postpone_callback, save_rollback},
+        undo::FieldSnapshot,
+    },
+    from_ref::weak_from_ptr,
+};
 
+/// Gives mutable access to a field of `Obj` and calls
+/// `EditedCallback::edited_field` on `Obj` when dropped.
+///
+/// Both pointers are derived from the `&mut Obj` the editor was created
+/// with, so the field is never aliased by the callback.
 pub struct Editor<'a, Obj: EditedCallback, Field> {
-    obj:       Rglica<Obj>,
-    field:     Rglica<Field>,
-    _lifetime: PhantomData<&'a Obj>,
+    obj:       NonNull<Obj>,
+    field:     NonNull<Field>,
+    field_id:  FieldId,
+    project:   fn(&mut Obj) -> &mut Field,
+    record:    Option<Box<dyn FnOnce() + 'a>>,
+    _lifetime: PhantomData<&'a mut Obj>,
 }
 
 impl<'a, Obj: EditedCallback, Field> Editor<'a, Obj, Field> {
-    pub fn new(obj: &'a Obj, field: &'a Field) -> Self {
+    pub fn new(obj: &'a mut Obj, project: fn(&mut Obj) -> &mut Field) -> Self {
+        Self::for_field(obj, project, FieldId::UNKNOWN)
+    }
+
+    pub fn for_field(obj: &'a mut Obj, project: fn(&mut Obj) -> &mut Field, field_id: FieldId) -> Self {
+        let mut obj = NonNull::from(obj);
+        let field = NonNull::from(project(unsafe { obj.as_mut() }));
+
         Self {
-            obj:       Rglica::from_ref(obj),
-            field:     Rglica::from_ref(field),
+            obj,
+            field,
+            field_id,
+            project,
+            record: None,
             _lifetime: PhantomData,
         }
     }
+
+    pub fn field_id(&self) -> FieldId {
+        self.field_id
+    }
+}
+
+impl<'a, Obj: EditedCallback + 'static, Field: Clone + 'static> Editor<'a, Obj, Field> {
+    /// Restores current field value if `EditTransaction` of `Obj` is rolled
+    /// back. Does nothing outside of transactions.
+    pub fn with_rollback(self) -> Self {
+        save_rollback(self.obj.as_ptr(), unsafe { self.field.as_ref() }, self.project);
+        self
+    }
+
+    /// Saves current field value to `stack` when the editor is dropped.
+    ///
+    /// `Obj` must be stored in `Own`.
+    pub fn record(self, stack: &'a UndoStack) -> Self {
+        self.record_with(move |snapshot| stack.push(snapshot))
+    }
+
+    /// Records the edit to `UndoStack::global`.
+    pub fn undoable(self) -> Self {
+        self.record_with(|snapshot| UndoStack::global().push(snapshot))
+    }
+
+    fn record_with(mut self, push: impl FnOnce(FieldSnapshot<Obj, Field>) + 'a) -> Self {
+        // Not derived from `self.obj` which is invalidated by later borrows of the
+        // `Own`.
+        let obj = weak_from_ptr(self.obj.as_ptr());
+
+        let snapshot = FieldSnapshot {
+            obj,
+            project: self.project,
+            field_id: self.field_id,
+            value: unsafe { self.field.as_ref() }.clone(),
+        };
+        self.record = Some(Box::new(move || push(snapshot)));
+        self
+    }
 }
 
 impl<Obj: EditedCallback, Field> Deref for Editor<'_, Obj, Field> {
     type Target = Field;
     fn deref(&self) -> &Self::Target {
-        self.field.deref()
+        unsafe { self.field.as_ref() }
     }
 }
 
 impl<Obj: EditedCallback, Field> DerefMut for Editor<'_, Obj, Field> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.field.deref_mut()
+        unsafe { self.field.as_mut() }
     }
 }
 
 impl<Obj: EditedCallback, Field> Drop for Editor<'_, Obj, Field> {
     fn drop(&mut self) {
-        self.obj.edited();
+        if let Some(record) = self.record.take() {
+            record();
+        }
+        if postpone_callback(self.obj.as_ptr()) {
+            return;
+        }
+        unsafe { self.obj.as_mut() }.edited_field(self.field_id);
     }
 }
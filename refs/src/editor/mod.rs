@@ -1,6 +1,13 @@
 mod callback;
 mod editor;
+mod observable;
 mod tests;
+mod transaction;
+mod undo;
 
-pub use callback::EditedCallback;
+pub use callback::{EditedCallback, FieldId};
 pub use editor::Editor;
+pub use observable::{Change, Observable, ObservableBatch, ObservableEditor};
+pub use refs_derive::Edit;
+pub use transaction::{Batch, EditTransaction};
+pub use undo::UndoStack;
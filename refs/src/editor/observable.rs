@@ -0,0 +1,176 @@
+use std::{
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    Weak,
+    event::{Event, SubscriptionId},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change<T> {
+    pub old: T,
+    pub new: T,
+}
+
+/// Value which notifies its subscribers when it is changed.
+///
+/// Subscribers receive old and new value. Setting equal value doesn't
+/// notify. Edits made through `batch` are delivered as a single change.
+/// Standalone from `Editor`; changes with subscribers must be made on main
+/// thread.
+pub struct Observable<T: Clone + PartialEq + 'static> {
+    value:     T,
+    changed:   Event<Change<T>>,
+    batch:     usize,
+    batch_old: Option<T>,
+}
+
+impl<T: Clone + PartialEq + 'static> Observable<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            changed: Event::new(),
+            batch: 0,
+            batch_old: None,
+        }
+    }
+
+    pub fn subscribe<Obj: ?Sized + 'static>(
+        &self,
+        target: Weak<Obj>,
+        f: impl FnMut(&mut Obj, Change<T>) + 'static,
+    ) -> SubscriptionId {
+        self.changed.subscribe(target, f)
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    pub fn set(&mut self, value: T) {
+        let old = std::mem::replace(&mut self.value, value);
+        self.changed_from(old);
+    }
+
+    /// Subscribers are notified when the editor is dropped.
+    pub fn edit(&mut self) -> ObservableEditor<'_, T> {
+        ObservableEditor {
+            old:        Some(self.value.clone()),
+            observable: self,
+        }
+    }
+
+    /// Changes made while the batch is alive are delivered as one
+    /// notification when it is dropped.
+    pub fn batch(&mut self) -> ObservableBatch<'_, T> {
+        self.batch += 1;
+        ObservableBatch { observable: self }
+    }
+
+    pub fn changed(&self) -> &Event<Change<T>> {
+        &self.changed
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    fn changed_from(&mut self, old: T) {
+        if self.batch > 0 {
+            self.batch_old.get_or_insert(old);
+            return;
+        }
+
+        if self.changed.has_subscribers() && old != self.value {
+            self.changed.trigger(Change {
+                old,
+                new: self.value.clone(),
+            });
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Deref for Observable<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Clone + PartialEq + Default + 'static> Default for Observable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Clone + PartialEq + Debug + 'static> Debug for Observable<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+pub struct ObservableEditor<'a, T: Clone + PartialEq + 'static> {
+    observable: &'a mut Observable<T>,
+    old:        Option<T>,
+}
+
+impl<T: Clone + PartialEq + 'static> Deref for ObservableEditor<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.observable.value
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> DerefMut for ObservableEditor<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.observable.value
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Drop for ObservableEditor<'_, T> {
+    fn drop(&mut self) {
+        if let Some(old) = self.old.take() {
+            self.observable.changed_from(old);
+        }
+    }
+}
+
+pub struct ObservableBatch<'a, T: Clone + PartialEq + 'static> {
+    observable: &'a mut Observable<T>,
+}
+
+impl<T: Clone + PartialEq + 'static> Deref for ObservableBatch<'_, T> {
+    type Target = Observable<T>;
+
+    fn deref(&self) -> &Observable<T> {
+        self.observable
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> DerefMut for ObservableBatch<'_, T> {
+    fn deref_mut(&mut self) -> &mut Observable<T> {
+        self.observable
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Drop for ObservableBatch<'_, T> {
+    fn drop(&mut self) {
+        self.observable.batch -= 1;
+
+        if self.observable.batch > 0 {
+            return;
+        }
+
+        let Some(old) = self.observable.batch_old.take() else {
+            return;
+        };
+
+        if !std::thread::panicking() {
+            self.observable.changed_from(old);
+        }
+    }
+}
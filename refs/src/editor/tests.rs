@@ -1,13 +1,13 @@
 #![cfg(test)]
 
-use std::ops::DerefMut;
+use std::{cell::RefCell, ops::DerefMut};
 
 use hreads::set_current_thread_as_main;
 use serial_test::serial;
 
 use crate::{
     Own,
-    editor::{EditedCallback, Editor},
+    editor::{Batch, Change, Edit, EditTransaction, EditedCallback, Editor, FieldId, Observable, UndoStack},
 };
 
 #[derive(Default)]
@@ -22,7 +22,7 @@ struct DataHolder {
 
 impl DataHolder {
     fn data(&mut self) -> Editor<'_, DataHolder, Data> {
-        Editor::new(self, &self.data)
+        Editor::new(self, |holder| &mut holder.data)
     }
 }
 
@@ -55,3 +55,437 @@ fn test_editor() {
     data.data().number = 40;
     assert_eq!(*test, 50);
 }
+
+#[derive(Default)]
+struct Label {
+    text: Observable<String>,
+}
+
+#[derive(Default)]
+struct Layout {
+    text_changes: usize,
+    changes:      Vec<Change<String>>,
+}
+
+#[test]
+#[serial]
+fn test_observable() {
+    set_current_thread_as_main();
+
+    let label = Own::new(Label::default());
+    let layout = Own::new(Layout::default());
+    let history = Own::new(Layout::default());
+    let mut label_weak = label.weak();
+
+    label
+        .text
+        .subscribe(history.weak(), |history, change| history.changes.push(change));
+    label.text.subscribe(layout.weak(), |layout, _| layout.text_changes += 1);
+
+    label_weak.text.set("a".into());
+    label_weak.text.set("a".into());
+    label_weak.text.edit().push('b');
+
+    assert_eq!(label.text.get(), "ab");
+    assert_eq!(
+        history.changes,
+        vec![
+            Change {
+                old: String::new(),
+                new: "a".into(),
+            },
+            Change {
+                old: "a".into(),
+                new: "ab".into(),
+            },
+        ]
+    );
+    assert_eq!(layout.text_changes, 2);
+
+    drop(layout);
+
+    label_weak.text.set("c".into());
+    assert_eq!(history.changes.len(), 3);
+    assert_eq!(label.text.changed().subscribers_count(), 1);
+}
+
+#[test]
+#[serial]
+fn test_observable_batch() {
+    set_current_thread_as_main();
+
+    let layout = Own::new(Layout::default());
+    let mut value = Observable::new(1);
+
+    value.subscribe(layout.weak(), |layout, change| {
+        assert_eq!(change, Change { old: 1, new: 4 });
+        layout.text_changes += 1;
+    });
+
+    {
+        let mut batch = value.batch();
+        batch.set(2);
+        *batch.edit() += 1;
+
+        let mut nested = batch.batch();
+        nested.set(4);
+    }
+
+    assert_eq!(*value, 4);
+    assert_eq!(layout.text_changes, 1);
+
+    {
+        let mut batch = value.batch();
+        batch.set(5);
+        batch.set(4);
+    }
+
+    assert_eq!(layout.text_changes, 1);
+}
+
+#[derive(Default, Edit)]
+struct Input {
+    text: Observable<String>,
+}
+
+impl EditedCallback for Input {
+    fn edited_field(&mut self, field: FieldId) {
+        EDITS.with_borrow_mut(|edits| edits.push(format!("input.{field}")));
+    }
+}
+
+#[test]
+#[serial]
+fn test_observable_field_editor() {
+    set_current_thread_as_main();
+
+    let mut input = Input::default();
+    let layout = Own::new(Layout::default());
+
+    input.text.subscribe(layout.weak(), |layout, change| {
+        EDITS.with_borrow_mut(|edits| edits.push(format!("changed to {}", change.new)));
+        layout.changes.push(change);
+    });
+
+    input.edit_text().set("a".into());
+    input.edit_text().edit().push('b');
+    assert_eq!(
+        take_edits(),
+        vec!["changed to a", "input.text", "changed to ab", "input.text"]
+    );
+
+    input.edit_text().set("ab".into());
+    assert_eq!(take_edits(), vec!["input.text"]);
+    assert_eq!(layout.changes.len(), 2);
+}
+
+#[test]
+#[serial]
+fn test_observable_off_main_thread() {
+    set_current_thread_as_main();
+
+    std::thread::spawn(|| {
+        let mut value = Observable::new(1);
+        value.set(2);
+        *value.edit() += 1;
+        value.batch().set(4);
+        assert_eq!(*value, 4);
+    })
+    .join()
+    .unwrap();
+}
+
+thread_local! {
+    static EDITS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+fn take_edits() -> Vec<String> {
+    EDITS.with_borrow_mut(std::mem::take)
+}
+
+#[derive(Default, Clone, Edit)]
+struct Size {
+    width:  f32,
+    height: f32,
+}
+
+impl EditedCallback for Size {
+    fn edited_field(&mut self, field: FieldId) {
+        EDITS.with_borrow_mut(|edits| edits.push(format!("size.{field}")));
+    }
+}
+
+#[derive(Default, Edit)]
+struct View {
+    name:   String,
+    size:   Size,
+    #[edit(skip)]
+    _cache: u32,
+}
+
+impl EditedCallback for View {
+    fn edited_field(&mut self, field: FieldId) {
+        let name = match field {
+            View::FIELD_NAME => "name",
+            View::FIELD_SIZE => "size",
+            _ => unreachable!(),
+        };
+        EDITS.with_borrow_mut(|edits| edits.push(format!("view.{name}")));
+    }
+}
+
+#[test]
+#[serial]
+fn test_edited_field() {
+    set_current_thread_as_main();
+
+    let mut view = View::default();
+
+    view.edit_name().push_str("button");
+    assert_eq!(view.name, "button");
+    assert_eq!(take_edits(), vec!["view.name"]);
+
+    *view.edit_size() = Size {
+        width:  1.0,
+        height: 2.0,
+    };
+    assert_eq!(take_edits(), vec!["view.size"]);
+
+    assert_eq!(View::FIELD_NAME.name(), "name");
+    assert_ne!(View::FIELD_NAME, View::FIELD_SIZE);
+}
+
+#[test]
+#[serial]
+fn test_nested_editors() {
+    set_current_thread_as_main();
+
+    let mut view = View::default();
+
+    *view.edit_size().edit_width() = 10.0;
+    assert_eq!(take_edits(), vec!["size.width", "view.size"]);
+
+    {
+        let mut size = view.edit_size();
+        *size.edit_width() = 20.0;
+        *size.edit_height() = 30.0;
+        assert_eq!(take_edits(), vec!["size.width", "size.height"]);
+    }
+    assert_eq!(take_edits(), vec!["view.size"]);
+
+    assert_eq!(view.size.width, 20.0);
+    assert_eq!(view.size.height, 30.0);
+}
+
+#[test]
+#[serial]
+fn test_undo_redo() {
+    set_current_thread_as_main();
+
+    let stack = UndoStack::new();
+    let mut view = Own::new(View::default());
+
+    view.edit_name().record(&stack).push('a');
+    view.edit_name().record(&stack).push('b');
+    view.edit_size().record(&stack).width = 5.0;
+    take_edits();
+
+    assert!(stack.undo());
+    assert_eq!(view.size.width, 0.0);
+    assert_eq!(take_edits(), vec!["view.size"]);
+
+    assert!(stack.undo());
+    assert_eq!(view.name, "a");
+
+    assert!(stack.redo());
+    assert_eq!(view.name, "ab");
+    assert_eq!(take_edits(), vec!["view.name", "view.name"]);
+
+    assert!(stack.can_redo());
+    view.edit_name().record(&stack).push('c');
+    assert!(!stack.can_redo());
+
+    assert!(stack.undo());
+    assert!(stack.undo());
+    assert!(stack.undo());
+    assert!(!stack.undo());
+    assert_eq!(view.name, "");
+
+    assert!(stack.redo());
+    assert_eq!(view.name, "a");
+
+    view.edit_name().undoable().push_str("global");
+    assert!(UndoStack::global().undo());
+    assert_eq!(view.name, "a");
+    UndoStack::global().clear();
+}
+
+#[test]
+#[serial]
+fn test_undo_skips_freed_objects() {
+    set_current_thread_as_main();
+
+    let stack = UndoStack::new();
+    let mut first = Own::new(View::default());
+    let mut second = Own::new(View::default());
+
+    first.edit_name().record(&stack).push_str("first");
+    second.edit_name().record(&stack).push_str("second");
+
+    drop(second);
+
+    assert!(stack.undo());
+    assert_eq!(first.name, "");
+    assert!(!stack.undo());
+    assert!(!stack.can_undo());
+    take_edits();
+}
+
+#[derive(Default, Edit)]
+struct Form {
+    #[edit(rollback)]
+    title: String,
+    #[edit(rollback)]
+    count: i32,
+    #[edit(rollback)]
+    size:  Size,
+    note:  String,
+}
+
+impl EditedCallback for Form {
+    fn edited(&mut self) {
+        EDITS.with_borrow_mut(|edits| edits.push("form".to_string()));
+    }
+}
+
+#[test]
+#[serial]
+fn test_batch() {
+    set_current_thread_as_main();
+
+    let mut form = Form::default();
+
+    let result = form.batch(|form| {
+        form.edit_title().push_str("title");
+        *form.edit_count() = 5;
+        form.edit_size().edit_width();
+        form.batch(|form| *form.edit_count() += 1);
+        assert_eq!(take_edits(), vec!["size.width"]);
+        10
+    });
+
+    assert_eq!(result, 10);
+    assert_eq!(take_edits(), vec!["form"]);
+    assert_eq!(form.title, "title");
+    assert_eq!(form.count, 6);
+
+    form.batch(|form| form.count = 1);
+    assert!(take_edits().is_empty());
+
+    *form.edit_count() = 2;
+    assert_eq!(take_edits(), vec!["form"]);
+}
+
+#[test]
+#[serial]
+fn test_transaction_rollback() {
+    set_current_thread_as_main();
+
+    let mut form = Form {
+        title: "old".into(),
+        ..Default::default()
+    };
+
+    {
+        let mut transaction = EditTransaction::new(&mut form);
+        transaction.edit_title().push_str("_new");
+        *transaction.edit_count() = 1;
+
+        let mut nested = EditTransaction::new(&mut *transaction);
+        *nested.edit_count() = 2;
+        nested.edit_note().push_str("kept");
+        nested.rollback();
+
+        assert_eq!(transaction.count, 1);
+        transaction.rollback();
+    }
+
+    assert_eq!(form.title, "old");
+    assert_eq!(form.count, 0);
+    assert_eq!(form.note, "kept");
+    assert!(take_edits().is_empty());
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        form.batch(|form| {
+            *form.edit_count() = 10;
+            form.edit_size().width = 10.0;
+            form.edit_title().clear();
+            panic!("edit failed");
+        })
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(form.title, "old");
+    assert_eq!(form.count, 0);
+    assert_eq!(form.size.width, 0.0);
+    assert!(take_edits().is_empty());
+}
+
+#[repr(C)]
+#[derive(Default, Edit)]
+struct Outer {
+    #[edit(rollback)]
+    size:  Size,
+    count: i32,
+}
+
+impl EditedCallback for Outer {
+    fn edited(&mut self) {
+        EDITS.with_borrow_mut(|edits| edits.push("outer".to_string()));
+    }
+}
+
+#[test]
+#[serial]
+fn test_batch_field_at_same_address() {
+    set_current_thread_as_main();
+
+    let mut outer = Outer::default();
+    assert_eq!(
+        std::ptr::from_ref(&outer).addr(),
+        std::ptr::from_ref(&outer.size).addr()
+    );
+
+    outer.batch(|outer| *outer.edit_size().edit_width() = 2.0);
+    assert_eq!(take_edits(), vec!["size.width", "outer"]);
+
+    {
+        let mut transaction = EditTransaction::new(&mut outer);
+        *transaction.edit_size().edit_width() = 3.0;
+        transaction.rollback();
+    }
+    assert_eq!(outer.size.width, 2.0);
+    assert_eq!(take_edits(), vec!["size.width"]);
+}
+
+#[test]
+#[serial]
+fn test_editor_off_main_thread() {
+    set_current_thread_as_main();
+
+    let mut form = Form::default();
+
+    form.batch(|form| {
+        std::thread::spawn(|| {
+            let mut view = View::default();
+            *view.edit_size().edit_width() = 5.0;
+            assert_eq!(take_edits(), vec!["size.width", "view.size"]);
+        })
+        .join()
+        .unwrap();
+
+        *form.edit_count() = 1;
+    });
+
+    assert_eq!(take_edits(), vec!["form"]);
+}
@@ -0,0 +1,199 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    ptr::from_mut,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use hreads::is_main_thread;
+
+use crate::{Addr, editor::EditedCallback, main_lock::MainLock};
+
+type Rollback = Box<dyn FnOnce(*mut u8)>;
+
+/// Object address and type. A field at offset 0 has the same address as its
+/// owner.
+type Key = (Addr, TypeId);
+
+#[derive(Default)]
+struct TransactionState {
+    depth:    usize,
+    edited:   bool,
+    rollback: Vec<Rollback>,
+}
+
+static TRANSACTIONS: MainLock<HashMap<Key, TransactionState>> = MainLock::new();
+
+/// Number of open transactions. Editors don't touch `TRANSACTIONS` when it is
+/// 0 so they can be used off main thread.
+static OPEN: AtomicUsize = AtomicUsize::new(0);
+
+/// `TypeId` of `T` with lifetimes erased so editors of non `'static` objects
+/// can be keyed too.
+fn type_key<T: ?Sized>() -> TypeId {
+    trait NonStaticAny {
+        fn type_key(&self) -> TypeId
+        where Self: 'static;
+    }
+
+    impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+        fn type_key(&self) -> TypeId
+        where Self: 'static {
+            TypeId::of::<T>()
+        }
+    }
+
+    let phantom = PhantomData::<T>;
+    // Lifetimes don't take part in `TypeId`.
+    NonStaticAny::type_key(unsafe {
+        std::mem::transmute::<&dyn NonStaticAny, &(dyn NonStaticAny + 'static)>(&phantom)
+    })
+}
+
+fn key<Obj>(obj: *const Obj) -> Key {
+    (obj.cast::<u8>() as Addr, type_key::<Obj>())
+}
+
+fn is_open() -> bool {
+    OPEN.load(Ordering::Relaxed) > 0 && is_main_thread()
+}
+
+/// Returns `true` if `obj` is edited inside a transaction.
+/// In this case the edit is remembered and the callback is postponed until
+/// commit.
+pub(crate) fn postpone_callback<Obj>(obj: *const Obj) -> bool {
+    if !is_open() {
+        return false;
+    }
+    let mut transactions = TRANSACTIONS.get_mut();
+    let Some(state) = transactions.get_mut(&key(obj)) else {
+        return false;
+    };
+    state.edited = true;
+    true
+}
+
+/// Remembers current value of the field to restore it if the transaction
+/// editing `obj` is rolled back.
+pub(crate) fn save_rollback<Obj: 'static, Field: Clone + 'static>(
+    obj: *const Obj,
+    field: &Field,
+    project: fn(&mut Obj) -> &mut Field,
+) {
+    if !is_open() {
+        return;
+    }
+    let mut transactions = TRANSACTIONS.get_mut();
+    let Some(state) = transactions.get_mut(&key(obj)) else {
+        return;
+    };
+
+    let value = field.clone();
+    state.rollback.push(Box::new(move |obj| {
+        *project(unsafe { &mut *obj.cast::<Obj>() }) = value;
+    }));
+}
+
+/// Suppresses `EditedCallback` calls from editors of the object until
+/// the transaction is dropped. Then `EditedCallback::edited` is called once
+/// if anything was edited.
+///
+/// Nested transactions of the same object are committed with the outermost one.
+/// If the transaction is dropped during a panic or with `rollback`, fields
+/// edited with `Editor::with_rollback` during it are restored and the
+/// outermost transaction doesn't call the callback.
+pub struct EditTransaction<'a, Obj: EditedCallback> {
+    obj:           &'a mut Obj,
+    rollback_from: usize,
+    rollback:      bool,
+}
+
+impl<'a, Obj: EditedCallback> EditTransaction<'a, Obj> {
+    pub fn new(obj: &'a mut Obj) -> Self {
+        let rollback_from = {
+            let mut transactions = TRANSACTIONS.get_mut();
+            let state = transactions.entry(key(obj)).or_default();
+            state.depth += 1;
+            state.rollback.len()
+        };
+        OPEN.fetch_add(1, Ordering::Relaxed);
+
+        Self {
+            obj,
+            rollback_from,
+            rollback: false,
+        }
+    }
+
+    pub fn commit(self) {}
+
+    /// Restores fields edited with `Editor::with_rollback` during this
+    /// transaction.
+    pub fn rollback(mut self) {
+        self.rollback = true;
+    }
+}
+
+impl<Obj: EditedCallback> Deref for EditTransaction<'_, Obj> {
+    type Target = Obj;
+
+    fn deref(&self) -> &Obj {
+        self.obj
+    }
+}
+
+impl<Obj: EditedCallback> DerefMut for EditTransaction<'_, Obj> {
+    fn deref_mut(&mut self) -> &mut Obj {
+        self.obj
+    }
+}
+
+impl<Obj: EditedCallback> Drop for EditTransaction<'_, Obj> {
+    fn drop(&mut self) {
+        let key = key(self.obj);
+        let rollback = self.rollback || std::thread::panicking();
+
+        let (rollback_entries, call_edited) = {
+            let mut transactions = TRANSACTIONS.get_mut();
+            let state = transactions.get_mut(&key).expect("Transaction state not found");
+
+            let entries: Vec<_> = if rollback {
+                state.rollback.drain(self.rollback_from..).collect()
+            } else {
+                vec![]
+            };
+
+            state.depth -= 1;
+
+            let call_edited = if state.depth == 0 {
+                transactions.remove(&key).is_some_and(|state| state.edited) && !rollback
+            } else {
+                false
+            };
+
+            (entries, call_edited)
+        };
+        OPEN.fetch_sub(1, Ordering::Relaxed);
+
+        let obj = from_mut(self.obj).cast::<u8>();
+
+        for entry in rollback_entries.into_iter().rev() {
+            entry(obj);
+        }
+
+        if call_edited {
+            self.obj.edited();
+        }
+    }
+}
+
+pub trait Batch: EditedCallback + Sized {
+    /// Edits the object inside `EditTransaction`.
+    fn batch<R>(&mut self, edit: impl FnOnce(&mut EditTransaction<Self>) -> R) -> R {
+        edit(&mut EditTransaction::new(self))
+    }
+}
+
+impl<T: EditedCallback> Batch for T {}
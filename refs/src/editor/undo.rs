@@ -0,0 +1,104 @@
+use std::cell::RefCell;
+
+use crate::{
+    Weak,
+    editor::{EditedCallback, FieldId},
+    main_lock::{MainLock, MainLockRef},
+};
+
+type Entries = RefCell<Vec<Box<dyn UndoEntry>>>;
+
+static GLOBAL: MainLock<UndoStack> = MainLock::new();
+
+trait UndoEntry {
+    /// Swaps stored value with the current field value.
+    /// Returns `false` if the edited object was freed.
+    fn apply(&mut self) -> bool;
+}
+
+pub(crate) struct FieldSnapshot<Obj: EditedCallback + 'static, Field: 'static> {
+    pub(crate) obj:      Weak<Obj>,
+    pub(crate) project:  fn(&mut Obj) -> &mut Field,
+    pub(crate) field_id: FieldId,
+    pub(crate) value:    Field,
+}
+
+impl<Obj: EditedCallback, Field> UndoEntry for FieldSnapshot<Obj, Field> {
+    fn apply(&mut self) -> bool {
+        let Some(obj) = self.obj.get_mut() else {
+            return false;
+        };
+
+        std::mem::swap((self.project)(obj), &mut self.value);
+        obj.edited_field(self.field_id);
+
+        true
+    }
+}
+
+/// History of edits recorded with `Editor::record`.
+///
+/// `undo` and `redo` restore recorded values and call
+/// `EditedCallback::edited_field` on the edited object.
+/// Entries for freed objects are skipped.
+#[derive(Default)]
+pub struct UndoStack {
+    undo: Entries,
+    redo: Entries,
+}
+
+impl UndoStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stack used by `Editor::undoable`.
+    pub fn global() -> MainLockRef<'static, UndoStack> {
+        GLOBAL.get()
+    }
+
+    pub(crate) fn push<Obj: EditedCallback + 'static, Field: 'static>(
+        &self,
+        snapshot: FieldSnapshot<Obj, Field>,
+    ) {
+        self.undo.borrow_mut().push(Box::new(snapshot));
+        self.redo.borrow_mut().clear();
+    }
+
+    /// Returns `false` if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        Self::move_entry(&self.undo, &self.redo)
+    }
+
+    /// Returns `false` if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        Self::move_entry(&self.redo, &self.undo)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.borrow().is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.borrow().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.undo.borrow_mut().clear();
+        self.redo.borrow_mut().clear();
+    }
+
+    fn move_entry(from: &Entries, to: &Entries) -> bool {
+        // Entry is popped before applying so edited callbacks can use the stack.
+        loop {
+            let Some(mut entry) = from.borrow_mut().pop() else {
+                return false;
+            };
+
+            if entry.apply() {
+                to.borrow_mut().push(entry);
+                return true;
+            }
+        }
+    }
+}
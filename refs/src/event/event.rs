@@ -0,0 +1,248 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use crate::{
+    Weak,
+    main_lock::MainLock,
+    own::{Addr, Stamp},
+};
+
+/// Identity of subscriber target.
+type Target = (Addr, Stamp);
+type Deferred = Box<dyn FnOnce()>;
+
+/// Targets of subscribers which are running higher in the stack with
+/// deliveries postponed until they return.
+static DISPATCHING: MainLock<Vec<(Target, Vec<Deferred>)>> = MainLock::new();
+
+/// Marks target as dispatching until dropped. Runs deliveries postponed
+/// for the target when dropped.
+struct Dispatching(Target);
+
+impl Dispatching {
+    /// Returns `None` if a subscriber of `target` is already running.
+    fn start(target: Target) -> Option<Self> {
+        let mut dispatching = DISPATCHING.get_mut();
+        if dispatching.iter().any(|(running, _)| *running == target) {
+            return None;
+        }
+        dispatching.push((target, vec![]));
+        Some(Self(target))
+    }
+
+    fn postpone(target: Target, deliver: impl FnOnce() + 'static) {
+        let mut dispatching = DISPATCHING.get_mut();
+        if let Some((_, deferred)) = dispatching.iter_mut().find(|(running, _)| *running == target) {
+            deferred.push(Box::new(deliver));
+        }
+    }
+}
+
+impl Drop for Dispatching {
+    fn drop(&mut self) {
+        let deferred = {
+            let mut dispatching = DISPATCHING.get_mut();
+            let Some(pos) = dispatching.iter().rposition(|(running, _)| *running == self.0) else {
+                return;
+            };
+            dispatching.remove(pos).1
+        };
+
+        if std::thread::panicking() {
+            return;
+        }
+
+        for deliver in deferred {
+            deliver();
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+struct Subscriber<A> {
+    id:       SubscriptionId,
+    target:   Weak,
+    once:     bool,
+    active:   Cell<bool>,
+    callback: RefCell<Box<dyn FnMut(A)>>,
+}
+
+/// Typed event with weak subscribers.
+///
+/// Subscribers whose target was freed are skipped and removed
+/// automatically, including targets freed during dispatch.
+/// Can be used only from main thread.
+pub struct Event<A = ()> {
+    subscribers: RefCell<Vec<Rc<Subscriber<A>>>>,
+    last_id:     Cell<u64>,
+}
+
+unsafe impl<A> Send for Event<A> {}
+unsafe impl<A> Sync for Event<A> {}
+
+impl<A> Event<A> {
+    pub const fn new() -> Self {
+        Self {
+            subscribers: RefCell::new(Vec::new()),
+            last_id:     Cell::new(0),
+        }
+    }
+
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        Self::check();
+        let mut removed = false;
+        self.subscribers.borrow_mut().retain(|sub| {
+            if sub.id == id {
+                sub.active.set(false);
+                removed = true;
+            }
+            sub.id != id
+        });
+        removed
+    }
+
+    /// Removes all subscriptions of `target`.
+    pub fn unsubscribe_target<T: ?Sized>(&self, target: Weak<T>) {
+        Self::check();
+        let target = target.raw();
+        self.subscribers.borrow_mut().retain(|sub| {
+            let keep = sub.target.raw() != target;
+            sub.active.set(keep);
+            keep
+        });
+    }
+
+    /// Number of subscribers with live targets.
+    pub fn subscribers_count(&self) -> usize {
+        Self::check();
+        self.subscribers
+            .borrow()
+            .iter()
+            .filter(|sub| sub.active.get() && sub.target.is_ok())
+            .count()
+    }
+
+    /// Can be called from any thread since `&mut self` is not shared.
+    pub(crate) fn has_subscribers(&mut self) -> bool {
+        !self.subscribers.get_mut().is_empty()
+    }
+
+    pub fn clear(&self) {
+        Self::check();
+        for sub in self.subscribers.take() {
+            sub.active.set(false);
+        }
+    }
+
+    fn check() {
+        hreads::assert_main_thread();
+    }
+}
+
+impl<A> Drop for Event<A> {
+    fn drop(&mut self) {
+        // Subscriber callbacks can capture values which are not `Send`.
+        if !self.subscribers.get_mut().is_empty() {
+            Self::check();
+        }
+    }
+}
+
+impl<A: 'static> Event<A> {
+    pub fn subscribe<T: ?Sized + 'static>(
+        &self,
+        target: Weak<T>,
+        f: impl FnMut(&mut T, A) + 'static,
+    ) -> SubscriptionId {
+        self.add_subscriber(target, f, false)
+    }
+
+    /// Subscriber is removed after first call.
+    pub fn subscribe_once<T: ?Sized + 'static>(
+        &self,
+        target: Weak<T>,
+        f: impl FnMut(&mut T, A) + 'static,
+    ) -> SubscriptionId {
+        self.add_subscriber(target, f, true)
+    }
+
+    fn add_subscriber<T: ?Sized + 'static>(
+        &self,
+        target: Weak<T>,
+        f: impl FnMut(&mut T, A) + 'static,
+        once: bool,
+    ) -> SubscriptionId {
+        Self::check();
+
+        let id = SubscriptionId(self.last_id.get() + 1);
+        self.last_id.set(id.0);
+
+        self.subscribers.borrow_mut().push(Rc::new(Subscriber {
+            id,
+            target: target.erase(),
+            once,
+            active: Cell::new(true),
+            callback: RefCell::new(Box::new(target.bind1(f))),
+        }));
+
+        id
+    }
+}
+
+impl<A: Clone + 'static> Event<A> {
+    /// Calls all live subscribers in subscription order. Subscribers added
+    /// during dispatch are called starting from the next trigger.
+    ///
+    /// If a subscriber of the same target is already running higher in the
+    /// stack, for this or any other event, delivery to the target is
+    /// postponed until that subscriber returns.
+    pub fn trigger(&self, args: A) {
+        Self::check();
+
+        let subscribers = self.subscribers.borrow().clone();
+
+        for sub in subscribers {
+            Self::deliver(sub, args.clone());
+        }
+
+        self.subscribers
+            .borrow_mut()
+            .retain(|sub| sub.active.get() && sub.target.is_ok());
+    }
+
+    fn deliver(sub: Rc<Subscriber<A>>, args: A) {
+        if !sub.active.get() || sub.target.is_null() {
+            return;
+        }
+
+        let target = sub.target.identity();
+
+        // Running subscriber holds mutable reference to the target.
+        let Some(dispatching) = Dispatching::start(target) else {
+            Dispatching::postpone(target, move || Self::deliver(sub, args));
+            return;
+        };
+
+        {
+            let mut callback = sub.callback.borrow_mut();
+
+            if sub.once {
+                sub.active.set(false);
+            }
+
+            callback(args);
+        }
+
+        drop(dispatching);
+    }
+}
+
+impl<A> Default for Event<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
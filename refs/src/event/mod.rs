@@ -0,0 +1,4 @@
+mod event;
+mod tests;
+
+pub use event::{Event, SubscriptionId};
@@ -0,0 +1,216 @@
+#![cfg(test)]
+
+use std::{cell::RefCell, rc::Rc};
+
+use hreads::set_current_thread_as_main;
+use serial_test::serial;
+
+use crate::{Own, Weak, event::Event};
+
+#[derive(Default)]
+struct Listener {
+    received: Vec<i32>,
+}
+
+impl Listener {
+    fn on_value(&mut self, val: i32) {
+        self.received.push(val);
+    }
+}
+
+#[test]
+#[serial]
+fn event_dispatch() {
+    set_current_thread_as_main();
+
+    let event = Event::<i32>::new();
+
+    let a = Own::new(Listener::default());
+    let b = Own::new(Listener::default());
+
+    event.subscribe(a.weak(), Listener::on_value);
+    event.subscribe(b.weak(), |this, val| this.on_value(val * 10));
+
+    event.trigger(1);
+    event.trigger(2);
+
+    assert_eq!(a.received, vec![1, 2]);
+    assert_eq!(b.received, vec![10, 20]);
+    assert_eq!(event.subscribers_count(), 2);
+}
+
+#[test]
+#[serial]
+fn event_prunes_freed() {
+    set_current_thread_as_main();
+
+    let event = Event::<i32>::new();
+
+    let a = Own::new(Listener::default());
+    let b = Own::new(Listener::default());
+
+    event.subscribe(a.weak(), Listener::on_value);
+    event.subscribe(b.weak(), Listener::on_value);
+
+    drop(b);
+
+    assert_eq!(event.subscribers_count(), 1);
+
+    event.trigger(5);
+
+    assert_eq!(a.received, vec![5]);
+}
+
+#[test]
+#[serial]
+fn event_once() {
+    set_current_thread_as_main();
+
+    let event = Event::<i32>::new();
+
+    let a = Own::new(Listener::default());
+
+    event.subscribe_once(a.weak(), Listener::on_value);
+
+    event.trigger(1);
+    event.trigger(2);
+
+    assert_eq!(a.received, vec![1]);
+    assert_eq!(event.subscribers_count(), 0);
+}
+
+#[test]
+#[serial]
+fn event_unsubscribe() {
+    set_current_thread_as_main();
+
+    let event = Event::<i32>::new();
+
+    let a = Own::new(Listener::default());
+    let b = Own::new(Listener::default());
+
+    let id = event.subscribe(a.weak(), Listener::on_value);
+    event.subscribe(b.weak(), Listener::on_value);
+    event.subscribe(b.weak(), |this, val| this.on_value(-val));
+
+    assert!(event.unsubscribe(id));
+    assert!(!event.unsubscribe(id));
+
+    event.trigger(1);
+
+    event.unsubscribe_target(b.weak());
+
+    event.trigger(2);
+
+    assert!(a.received.is_empty());
+    assert_eq!(b.received, vec![1, -1]);
+}
+
+#[test]
+#[serial]
+fn event_freed_during_dispatch() {
+    set_current_thread_as_main();
+
+    struct Killer {
+        victim: Option<Own<Listener>>,
+    }
+
+    let event = Rc::new(Event::<i32>::new());
+
+    let killer = Own::new(Killer { victim: None });
+    let victim = Own::new(Listener::default());
+    let victim_weak = victim.weak();
+
+    let mut killer_weak = killer.weak();
+    killer_weak.victim = Some(victim);
+
+    event.subscribe(killer.weak(), |this, _| {
+        this.victim = None;
+    });
+    event.subscribe(victim_weak, Listener::on_value);
+
+    event.trigger(1);
+
+    assert!(victim_weak.is_null());
+    assert_eq!(event.subscribers_count(), 1);
+}
+
+#[test]
+#[serial]
+fn event_reentrant() {
+    set_current_thread_as_main();
+
+    struct Relay {
+        event: Rc<Event<i32>>,
+        log:   Rc<RefCell<Vec<i32>>>,
+    }
+
+    let event = Rc::new(Event::<i32>::new());
+    let log = Rc::new(RefCell::new(vec![]));
+
+    let relay = Own::new(Relay {
+        event: event.clone(),
+        log:   log.clone(),
+    });
+    let listener = Own::new(Listener::default());
+
+    event.subscribe(relay.weak(), |this, val| {
+        this.log.borrow_mut().push(val);
+        if val < 3 {
+            this.event.trigger(val + 1);
+        }
+    });
+    event.subscribe(listener.weak(), Listener::on_value);
+    // Postponed by nested triggers until the first relay subscriber returns.
+    event.subscribe(relay.weak(), |this, val| this.log.borrow_mut().push(-val));
+
+    let late = Own::new(Listener::default());
+    let late_weak: Weak<Listener> = late.weak();
+    event.subscribe_once(relay.weak(), move |this, _| {
+        this.event.subscribe(late_weak, Listener::on_value);
+    });
+
+    event.trigger(1);
+
+    assert_eq!(*log.borrow(), vec![1, 2, 3, -3, -2, -1]);
+    assert_eq!(listener.received, vec![2, 3, 1]);
+    assert!(late.received.is_empty());
+
+    event.trigger(10);
+    assert_eq!(*log.borrow(), vec![1, 2, 3, -3, -2, -1, 10, -10]);
+    assert_eq!(late.received, vec![10]);
+}
+
+#[test]
+#[serial]
+fn event_reentrant_other_event() {
+    set_current_thread_as_main();
+
+    let first = Rc::new(Event::<i32>::new());
+    let second = Rc::new(Event::<i32>::new());
+
+    let listener = Own::new(Listener::default());
+    let other = Own::new(Listener::default());
+
+    let nested = second.clone();
+    first.subscribe(listener.weak(), move |this, val| {
+        this.on_value(val);
+        nested.trigger(val * 10);
+    });
+    second.subscribe(listener.weak(), Listener::on_value);
+    second.subscribe(other.weak(), Listener::on_value);
+
+    first.trigger(1);
+    assert_eq!(listener.received, vec![1, 10]);
+    assert_eq!(other.received, vec![10]);
+
+    second.trigger(2);
+    assert_eq!(listener.received, vec![1, 10, 2]);
+}
+
+#[test]
+#[should_panic(expected = "This operation can be called only from main thread")]
+fn event_off_main_thread() {
+    static EVENT: Event<i32> = Event::new();
+    EVENT.trigger(1);
+}
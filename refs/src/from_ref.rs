@@ -18,6 +18,26 @@ pub fn weak_from_ref<T: ?Sized>(rf: &T) -> Weak<T> {
     }
 }
 
+/// Same as `weak_from_ref` but the object can be mutated through the returned
+/// `Weak`.
+///
+/// Pointer is restored from the provenance exposed by `Own` on allocation, so
+/// it stays valid after `ptr` and references it was derived from are
+/// invalidated.
+pub(crate) fn weak_from_ptr<T>(ptr: *mut T) -> Weak<T> {
+    let address = ptr.addr();
+
+    let Some(stamp) = RefCounter::stamp_for_address(address) else {
+        panic!("Trying to get weak pointer for object which is not managed by reference counter.")
+    };
+
+    Weak {
+        ptr: std::ptr::with_exposed_provenance_mut(address),
+        stamp,
+        type_name: std::any::type_name::<T>(),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Deref;
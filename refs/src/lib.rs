@@ -6,12 +6,19 @@
 #![feature(core_intrinsics)]
 #![feature(const_type_name)]
 #![feature(arbitrary_self_types)]
+#![feature(allocator_api)]
+#![feature(layout_for_ptr)]
+
+extern crate self as refs;
 
 mod as_any;
 mod erased;
 mod from_ref;
 mod into_own;
+mod observer;
+mod on_drop;
 mod own;
+mod own_pool;
 #[cfg(feature = "pointers_info")]
 mod pointers_info;
 mod raw_pointer;
@@ -21,17 +28,23 @@ mod rglica;
 mod serde;
 mod to_rglica;
 mod weak;
+mod weak_bind;
 
 pub use as_any::*;
 pub use erased::*;
 pub use from_ref::*;
+pub use observer::{ObserverId, RefsObserver, add_observer, remove_observer};
+pub use on_drop::{DropSubscription, on_any_drop};
 pub use own::*;
+pub use own_pool::{OwnPool, PoolAlloc};
 pub use raw_pointer::*;
 pub use rglica::*;
 pub use to_rglica::*;
 pub use weak::*;
 
+pub mod collections;
 pub mod editor;
+pub mod event;
 pub mod main_lock;
 pub mod manage;
 mod tests;
@@ -43,7 +56,7 @@ pub mod hreads {
 
 pub mod __internal_deps {
     pub use log::warn;
-    pub use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+    pub use parking_lot::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 }
 
 #[cfg(feature = "stats")]
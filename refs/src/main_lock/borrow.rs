@@ -0,0 +1,131 @@
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+
+/// Number of live guards and whether one of them is mutable.
+///
+/// Guards are counted in all builds so the value is never dropped or replaced
+/// while borrowed. Overlapping borrows are reported only in debug builds.
+#[derive(Default)]
+pub(crate) struct BorrowFlag {
+    guards:  Cell<usize>,
+    mutable: Cell<bool>,
+}
+
+impl BorrowFlag {
+    pub(crate) const fn new() -> Self {
+        Self {
+            guards:  Cell::new(0),
+            mutable: Cell::new(false),
+        }
+    }
+
+    #[cfg_attr(
+        not(debug_assertions),
+        allow(unused_variables, clippy::extra_unused_type_parameters)
+    )]
+    pub(crate) fn borrow<T>(&self, lock: &str) -> BorrowRef<'_> {
+        debug_assert!(
+            !self.mutable.get(),
+            "{lock}<{}> is already mutably borrowed",
+            std::any::type_name::<T>()
+        );
+        self.guards.set(self.guards.get() + 1);
+        BorrowRef { flag: self }
+    }
+
+    #[cfg_attr(
+        not(debug_assertions),
+        allow(unused_variables, clippy::extra_unused_type_parameters)
+    )]
+    pub(crate) fn borrow_mut<T>(&self, lock: &str) -> BorrowMut<'_> {
+        debug_assert_eq!(
+            self.guards.get(),
+            0,
+            "{lock}<{}> is already borrowed",
+            std::any::type_name::<T>()
+        );
+        self.guards.set(self.guards.get() + 1);
+        self.mutable.set(true);
+        BorrowMut { flag: self }
+    }
+
+    /// Panics if the value is borrowed. Checked in all builds.
+    /// Must be called before the value is dropped or replaced.
+    pub(crate) fn assert_unborrowed<T>(&self, lock: &str) {
+        assert_eq!(
+            self.guards.get(),
+            0,
+            "{lock}<{}> value can't be dropped or replaced while borrowed",
+            std::any::type_name::<T>()
+        );
+    }
+}
+
+pub(crate) struct BorrowRef<'a> {
+    flag: &'a BorrowFlag,
+}
+
+impl Drop for BorrowRef<'_> {
+    fn drop(&mut self) {
+        self.flag.guards.set(self.flag.guards.get() - 1);
+    }
+}
+
+pub(crate) struct BorrowMut<'a> {
+    flag: &'a BorrowFlag,
+}
+
+impl Drop for BorrowMut<'_> {
+    fn drop(&mut self) {
+        self.flag.guards.set(self.flag.guards.get() - 1);
+        self.flag.mutable.set(false);
+    }
+}
+
+/// Shared access to `MainLock` or `ThreadLock` value.
+pub struct MainLockRef<'a, T> {
+    val:     &'a T,
+    _borrow: BorrowRef<'a>,
+}
+
+impl<'a, T> MainLockRef<'a, T> {
+    pub(crate) fn new(borrow: BorrowRef<'a>, val: &'a T) -> Self {
+        Self { val, _borrow: borrow }
+    }
+}
+
+impl<T> Deref for MainLockRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.val
+    }
+}
+
+/// Mutable access to `MainLock` or `ThreadLock` value.
+pub struct MainLockMut<'a, T> {
+    val:     &'a mut T,
+    _borrow: BorrowMut<'a>,
+}
+
+impl<'a, T> MainLockMut<'a, T> {
+    pub(crate) fn new(borrow: BorrowMut<'a>, val: &'a mut T) -> Self {
+        Self { val, _borrow: borrow }
+    }
+}
+
+impl<T> Deref for MainLockMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.val
+    }
+}
+
+impl<T> DerefMut for MainLockMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.val
+    }
+}
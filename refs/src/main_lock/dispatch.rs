@@ -0,0 +1,130 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use hreads::assert_main_thread;
+use parking_lot::Mutex;
+
+use crate::main_lock::MainLock;
+
+type Task = Box<dyn FnOnce() + Send>;
+
+static QUEUE: Mutex<Vec<Task>> = Mutex::new(Vec::new());
+
+/// Queues `task` to be run on main thread during next `pump`.
+pub(crate) fn dispatch_task(task: impl FnOnce() + Send + 'static) {
+    QUEUE.lock().push(Box::new(task));
+}
+
+/// Runs tasks dispatched to main thread with `MainLock::dispatch`.
+/// Should be called regularly from the main loop.
+///
+/// Tasks dispatched while pumping are run on the next call.
+/// Returns number of tasks run.
+pub fn pump() -> usize {
+    assert_main_thread();
+
+    let tasks = std::mem::take(&mut *QUEUE.lock());
+    let count = tasks.len();
+
+    for task in tasks {
+        task();
+    }
+
+    count
+}
+
+struct DispatchState<R> {
+    result:  Option<R>,
+    dropped: bool,
+    waker:   Option<Waker>,
+}
+
+/// Completes when the task sent with `MainLock::dispatch_async` is run by
+/// `pump`.
+///
+/// Panics when polled if the task was dropped without running,
+/// for example if it panicked or the lock was not set.
+pub struct DispatchFuture<R> {
+    state: Arc<Mutex<DispatchState<R>>>,
+}
+
+impl<R> Future for DispatchFuture<R> {
+    type Output = R;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<R> {
+        let mut state = self.state.lock();
+
+        if let Some(result) = state.result.take() {
+            return Poll::Ready(result);
+        }
+
+        assert!(
+            !state.dropped,
+            "Task dispatched to main thread was dropped before completion"
+        );
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Marks the future as dropped if the task didn't complete.
+struct Completion<R> {
+    state: Arc<Mutex<DispatchState<R>>>,
+}
+
+impl<R> Completion<R> {
+    fn complete(self, result: R) {
+        self.state.lock().result = Some(result);
+    }
+}
+
+impl<R> Drop for Completion<R> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+        state.dropped = state.result.is_none();
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: 'static> MainLock<T> {
+    /// Runs `f` with the value on main thread during next `pump`.
+    /// Can be called from any thread. `f` is dropped if the lock is not set
+    /// by then.
+    pub fn dispatch(&'static self, f: impl FnOnce(&mut T) + Send + 'static) {
+        dispatch_task(move || {
+            let Some(mut value) = self.try_get_mut() else {
+                log::warn!(
+                    "Task dispatched to unset MainLock<{}> was dropped",
+                    std::any::type_name::<T>()
+                );
+                return;
+            };
+            f(&mut value);
+        });
+    }
+
+    /// Same as `dispatch` but returns the result of `f`.
+    pub fn dispatch_async<R: Send + 'static>(
+        &'static self,
+        f: impl FnOnce(&mut T) -> R + Send + 'static,
+    ) -> DispatchFuture<R> {
+        let state = Arc::new(Mutex::new(DispatchState {
+            result:  None,
+            dropped: false,
+            waker:   None,
+        }));
+
+        let completion = Completion { state: state.clone() };
+
+        self.dispatch(move |val| completion.complete(f(val)));
+
+        DispatchFuture { state }
+    }
+}
@@ -1,12 +1,26 @@
-#![allow(clippy::mut_from_ref)]
-
-use std::{cell::UnsafeCell, ops::Deref};
+use std::cell::{Cell, UnsafeCell};
 
 use hreads::assert_main_thread;
 
+use crate::{
+    Addr,
+    main_lock::{MainLockMut, MainLockRef, borrow::BorrowFlag, registry},
+};
+
+/// Value which can be accessed only from main thread.
+///
+/// Access goes through `MainLockRef` and `MainLockMut` guards. In debug builds
+/// they track borrows like `RefCell` and panic on overlapping mutable access.
+/// `set`, `take` and `reset` panic in all builds if the value is borrowed.
+/// Accessing the lock from its own initializer panics.
+///
+/// Initialized locks are registered for `main_lock::teardown_all`.
+/// Initialization requires `&'static self` so registered locks never move.
 #[derive(Default)]
 pub struct MainLock<T> {
-    val: UnsafeCell<Option<T>>,
+    val:          UnsafeCell<Option<T>>,
+    initializing: Cell<bool>,
+    borrow:       BorrowFlag,
 }
 
 unsafe impl<T> Send for MainLock<T> {}
@@ -15,47 +29,117 @@ unsafe impl<T> Sync for MainLock<T> {}
 impl<T> MainLock<T> {
     pub const fn new() -> Self {
         Self {
-            val: UnsafeCell::new(None),
+            val:          UnsafeCell::new(None),
+            initializing: Cell::new(false),
+            borrow:       BorrowFlag::new(),
         }
     }
 
-    fn get_internal(&self) -> &mut Option<T> {
+    fn addr(&self) -> Addr {
+        std::ptr::from_ref(self).addr()
+    }
+
+    fn check(&self) {
         assert_main_thread();
-        unsafe { self.val.get().as_mut().unwrap() }
+        assert!(
+            !self.initializing.get(),
+            "MainLock<{}> was accessed from its own initializer",
+            std::any::type_name::<T>()
+        );
     }
 
-    pub fn get_or_init(&self, init: impl Fn() -> T) -> &mut T {
-        let rf = self.get_internal();
+    unsafe fn teardown(lock: Addr) {
+        let lock = unsafe { &*std::ptr::with_exposed_provenance::<Self>(lock) };
+        drop(lock.take());
+    }
 
-        if rf.is_none() {
-            *rf = Some(init());
+    #[allow(clippy::mut_from_ref)]
+    fn value(&self) -> Option<&mut T> {
+        unsafe { self.val.get().as_mut().unwrap().as_mut() }
+    }
+
+    fn value_ref(&self) -> Option<&T> {
+        unsafe { self.val.get().as_ref().unwrap().as_ref() }
+    }
+
+    pub fn get_or_init(&'static self, init: impl FnOnce() -> T) -> MainLockMut<'static, T> {
+        self.check();
+
+        if !self.is_set() {
+            struct Initializing<'a>(&'a Cell<bool>);
+
+            impl Drop for Initializing<'_> {
+                fn drop(&mut self) {
+                    self.0.set(false);
+                }
+            }
+
+            self.initializing.set(true);
+            let guard = Initializing(&self.initializing);
+            let value = init();
+            drop(guard);
+
+            return self.set(value);
         }
 
-        rf.as_mut().unwrap()
+        let borrow = self.borrow.borrow_mut::<T>("MainLock");
+        MainLockMut::new(borrow, self.value().unwrap())
     }
 
-    pub fn set(&self, value: T) -> &mut T {
-        let rf = self.get_internal();
+    pub fn set(&'static self, value: T) -> MainLockMut<'static, T> {
+        self.check();
+        self.borrow.assert_unborrowed::<T>("MainLock");
+        let borrow = self.borrow.borrow_mut::<T>("MainLock");
+        let rf = unsafe { self.val.get().as_mut().unwrap() };
+
+        if rf.is_none() {
+            registry::register(std::ptr::from_ref(self).expose_provenance(), Self::teardown);
+        }
+
         *rf = Some(value);
-        rf.as_mut().unwrap()
+        MainLockMut::new(borrow, rf.as_mut().unwrap())
+    }
+
+    /// Takes the value out leaving the lock empty.
+    pub fn take(&self) -> Option<T> {
+        self.check();
+        self.borrow.assert_unborrowed::<T>("MainLock");
+        let value = unsafe { self.val.get().as_mut().unwrap() }.take();
+
+        if value.is_some() {
+            registry::unregister(self.addr());
+        }
+
+        value
+    }
+
+    /// Drops the value. Lock will be initialized again on next access.
+    pub fn reset(&self) {
+        drop(self.take());
     }
 
     pub fn is_set(&self) -> bool {
-        self.get_internal().is_some()
+        self.check();
+        self.value_ref().is_some()
     }
 
-    pub fn try_get(&self) -> Option<&T> {
-        self.get_internal().as_ref()
+    pub fn try_get(&self) -> Option<MainLockRef<'_, T>> {
+        self.check();
+        let borrow = self.borrow.borrow::<T>("MainLock");
+        self.value_ref().map(|val| MainLockRef::new(borrow, val))
     }
 
-    pub fn try_get_mut(&self) -> Option<&mut T> {
-        self.get_internal().as_mut()
+    pub fn try_get_mut(&self) -> Option<MainLockMut<'_, T>> {
+        self.check();
+        let borrow = self.borrow.borrow_mut::<T>("MainLock");
+        self.value().map(|val| MainLockMut::new(borrow, val))
     }
 
     /// # Safety
     ///
-    /// Caller must ensure that this call is performed on main thread
-    /// and that walue was already initialized
+    /// Caller must ensure that this call is performed on main thread,
+    /// that value was already initialized and that it is not borrowed
+    /// elsewhere.
     #[allow(clippy::mut_from_ref)]
     pub unsafe fn get_unchecked(&self) -> &mut T {
         unsafe { self.val.get().as_mut().unwrap().as_mut().unwrap() }
@@ -63,15 +147,15 @@ impl<T> MainLock<T> {
 }
 
 impl<T: Default> MainLock<T> {
-    pub fn get_mut(&self) -> &mut T {
-        self.get_or_init(|| T::default())
+    pub fn get(&'static self) -> MainLockRef<'static, T> {
+        if !self.is_set() {
+            drop(self.get_or_init(T::default));
+        }
+        let borrow = self.borrow.borrow::<T>("MainLock");
+        MainLockRef::new(borrow, self.value_ref().unwrap())
     }
-}
-
-impl<T: Default + 'static> Deref for MainLock<T> {
-    type Target = T;
 
-    fn deref(&self) -> &Self::Target {
-        self.get_mut()
+    pub fn get_mut(&'static self) -> MainLockMut<'static, T> {
+        self.get_or_init(T::default)
     }
 }
@@ -1,4 +1,13 @@
+mod borrow;
+mod dispatch;
 mod main_lock;
+mod registry;
 mod tests;
+mod thread_lock;
 
+pub use borrow::{MainLockMut, MainLockRef};
+pub(crate) use dispatch::dispatch_task;
+pub use dispatch::{DispatchFuture, pump};
 pub use main_lock::MainLock;
+pub use registry::teardown_all;
+pub use thread_lock::{ForeignThread, ThreadLock, ThreadLockMut, ThreadLockRef};
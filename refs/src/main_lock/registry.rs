@@ -0,0 +1,39 @@
+use hreads::assert_main_thread;
+use parking_lot::Mutex;
+
+use crate::Addr;
+
+struct Registered {
+    lock:     Addr,
+    teardown: unsafe fn(Addr),
+}
+
+/// Initialized locks in initialization order.
+static LOCKS: Mutex<Vec<Registered>> = Mutex::new(Vec::new());
+
+pub(crate) fn register(lock: Addr, teardown: unsafe fn(Addr)) {
+    LOCKS.lock().push(Registered { lock, teardown });
+}
+
+pub(crate) fn unregister(lock: Addr) {
+    LOCKS.lock().retain(|registered| registered.lock != lock);
+}
+
+/// Drops values of all initialized `MainLock`s in reverse initialization order.
+///
+/// Locks initialized while their dependents are dropped are dropped too.
+/// Panics if one of the values is borrowed.
+/// Locks can be used again after teardown and will be initialized from scratch.
+pub fn teardown_all() {
+    assert_main_thread();
+
+    loop {
+        let Some((lock, teardown)) = LOCKS.lock().last().map(|last| (last.lock, last.teardown)) else {
+            return;
+        };
+
+        // Teardown unregisters the lock. It stays registered if it is
+        // borrowed and teardown panics.
+        unsafe { teardown(lock) };
+    }
+}
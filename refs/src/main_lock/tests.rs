@@ -1,10 +1,21 @@
 #![cfg(test)]
 
+use std::{
+    cell::RefCell,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
 use hreads::set_current_thread_as_main;
 use serial_test::serial;
 use wasm_bindgen_test::wasm_bindgen_test;
 
-use crate::main_lock::MainLock;
+use crate::{
+    Own,
+    main_lock::{MainLock, ThreadLock, pump, teardown_all},
+};
 
 struct Data {
     a: i32,
@@ -23,11 +34,13 @@ static INIT_DATA: MainLock<Data> = MainLock::new();
 #[wasm_bindgen_test(unsupported = test)]
 fn test_main_lock() {
     set_current_thread_as_main();
-    assert_eq!(DATA.a, 20);
+    DATA.reset();
+    assert_eq!(DATA.get().a, 20);
     DATA.get_mut().a = 40;
-    assert_eq!(DATA.a, 40);
+    assert_eq!(DATA.get().a, 40);
     assert_eq!(DATA.set(Data { a: 77 }).a, 77);
-    assert_eq!(DATA.a, 77);
+    assert_eq!(DATA.get().a, 77);
+    DATA.reset();
 }
 
 #[serial]
@@ -38,14 +51,15 @@ fn test_get_or_init() {
     let data = INIT_DATA.get_or_init(|| Data { a: 44 });
 
     assert_eq!(data.a, 44);
+    drop(data);
 
-    assert_eq!(INIT_DATA.a, 44);
+    assert_eq!(INIT_DATA.get().a, 44);
 }
 
 #[test]
 #[should_panic(expected = "This operation can be called only from main thread")]
 fn fail_main_lock() {
-    _ = DATA.a;
+    _ = DATA.get().a;
 }
 
 struct NonDefault {
@@ -69,3 +83,383 @@ fn test_manual_init() {
     assert!(MANUAL_DATA.try_get().is_some());
     assert!(MANUAL_DATA.try_get_mut().is_some());
 }
+
+static ONCE_DATA: MainLock<Data> = MainLock::new();
+
+#[serial]
+#[wasm_bindgen_test(unsupported = test)]
+fn test_init_once() {
+    set_current_thread_as_main();
+
+    let value = String::from("moved into init");
+    let mut calls = 0;
+
+    assert_eq!(
+        ONCE_DATA
+            .get_or_init(|| {
+                calls += 1;
+                drop(value);
+                Data { a: 1 }
+            })
+            .a,
+        1
+    );
+    assert_eq!(ONCE_DATA.get_or_init(|| unreachable!()).a, 1);
+    assert_eq!(calls, 1);
+}
+
+static REENTRANT: MainLock<Data> = MainLock::new();
+
+#[test]
+#[serial]
+#[should_panic(expected = "MainLock<refs::main_lock::tests::Data> was accessed from its own initializer")]
+fn reentrant_init() {
+    set_current_thread_as_main();
+
+    REENTRANT.get_or_init(|| Data {
+        a: REENTRANT.get_or_init(|| Data { a: 1 }).a,
+    });
+}
+
+struct ReentrantDefault;
+
+static REENTRANT_DEFAULT: MainLock<ReentrantDefault> = MainLock::new();
+
+impl Default for ReentrantDefault {
+    fn default() -> Self {
+        _ = REENTRANT_DEFAULT.get();
+        Self
+    }
+}
+
+#[test]
+#[serial]
+#[should_panic(
+    expected = "MainLock<refs::main_lock::tests::ReentrantDefault> was accessed from its own initializer"
+)]
+fn reentrant_default() {
+    set_current_thread_as_main();
+
+    _ = REENTRANT_DEFAULT.get();
+}
+
+static FAILED_INIT: MainLock<Data> = MainLock::new();
+
+#[test]
+#[serial]
+fn panic_in_init() {
+    set_current_thread_as_main();
+
+    let result = std::panic::catch_unwind(|| {
+        FAILED_INIT.get_or_init(|| panic!("init failed"));
+    });
+
+    assert!(result.is_err());
+    assert!(!FAILED_INIT.is_set());
+    assert_eq!(FAILED_INIT.get_or_init(|| Data { a: 5 }).a, 5);
+}
+
+static BORROWED: MainLock<Data> = MainLock::new();
+
+#[test]
+#[serial]
+fn shared_borrows() {
+    set_current_thread_as_main();
+    BORROWED.reset();
+
+    let a = BORROWED.get();
+    let b = BORROWED.get();
+    assert_eq!(a.a + b.a, 40);
+    assert_eq!(BORROWED.get().a, 20);
+    drop((a, b));
+
+    BORROWED.get_mut().a = 30;
+    assert_eq!(BORROWED.try_get().unwrap().a, 30);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[serial]
+#[should_panic(expected = "MainLock<refs::main_lock::tests::Data> is already borrowed")]
+fn overlapping_mut_borrow() {
+    set_current_thread_as_main();
+
+    let _first = BORROWED.get_mut();
+    let _second = BORROWED.get_mut();
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[serial]
+#[should_panic(expected = "MainLock<refs::main_lock::tests::Data> is already mutably borrowed")]
+fn read_while_mut_borrowed() {
+    set_current_thread_as_main();
+
+    let _data = BORROWED.get_mut();
+    _ = BORROWED.get().a;
+}
+
+static TAKE_DATA: MainLock<Data> = MainLock::new();
+
+#[test]
+#[serial]
+fn take_and_reset() {
+    set_current_thread_as_main();
+
+    assert!(TAKE_DATA.take().is_none());
+
+    TAKE_DATA.get_mut().a = 5;
+    assert_eq!(TAKE_DATA.take().unwrap().a, 5);
+    assert!(!TAKE_DATA.is_set());
+
+    TAKE_DATA.get_mut().a = 6;
+    TAKE_DATA.reset();
+    assert!(!TAKE_DATA.is_set());
+    assert_eq!(TAKE_DATA.get().a, 20);
+    TAKE_DATA.reset();
+}
+
+static RESET_BORROWED: MainLock<Data> = MainLock::new();
+
+#[test]
+#[serial]
+#[should_panic(
+    expected = "MainLock<refs::main_lock::tests::Data> value can't be dropped or replaced while borrowed"
+)]
+fn reset_while_borrowed() {
+    set_current_thread_as_main();
+
+    let _data = RESET_BORROWED.get();
+    RESET_BORROWED.reset();
+}
+
+#[test]
+#[serial]
+#[should_panic(
+    expected = "MainLock<refs::main_lock::tests::Data> value can't be dropped or replaced while borrowed"
+)]
+fn set_while_borrowed() {
+    set_current_thread_as_main();
+
+    let _data = RESET_BORROWED.get();
+    RESET_BORROWED.set(Data { a: 1 });
+}
+
+#[test]
+#[should_panic(
+    expected = "ThreadLock<refs::main_lock::tests::Data> value can't be dropped or replaced while borrowed"
+)]
+fn thread_lock_take_while_borrowed() {
+    let lock = ThreadLock::<Data>::new();
+
+    let _data = lock.get();
+    _ = lock.take();
+}
+
+thread_local! {
+    static DROPPED: RefCell<Vec<&'static str>> = const { RefCell::new(vec![]) };
+}
+
+struct LogDrop(&'static str);
+
+impl Drop for LogDrop {
+    fn drop(&mut self) {
+        DROPPED.with_borrow_mut(|dropped| dropped.push(self.0));
+    }
+}
+
+static FIRST: MainLock<LogDrop> = MainLock::new();
+static SECOND: MainLock<LogDrop> = MainLock::new();
+static OWNED: MainLock<Own<i32>> = MainLock::new();
+
+#[test]
+#[serial]
+fn teardown_order() {
+    set_current_thread_as_main();
+
+    SECOND.set(LogDrop("second"));
+    FIRST.set(LogDrop("first"));
+    let weak = OWNED.set(Own::new(5)).weak();
+    SECOND.set(LogDrop("second replaced"));
+
+    teardown_all();
+
+    assert_eq!(
+        DROPPED.with_borrow_mut(std::mem::take),
+        vec!["second", "first", "second replaced"]
+    );
+    assert!(weak.is_null());
+    assert!(!FIRST.is_set());
+    assert!(!SECOND.is_set());
+    assert!(!OWNED.is_set());
+
+    FIRST.set(LogDrop("again"));
+    teardown_all();
+    assert_eq!(DROPPED.with_borrow_mut(std::mem::take), vec!["again"]);
+}
+
+#[test]
+fn thread_lock_binds_to_first_thread() {
+    let lock = ThreadLock::<Data>::new();
+
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                assert!(lock.try_get().is_none());
+                assert!(lock.owner().is_none());
+            })
+            .join()
+            .unwrap();
+
+        let owner = scope
+            .spawn(|| {
+                lock.get_mut().a = 10;
+                assert_eq!(lock.get().a, 10);
+                std::thread::current().id()
+            })
+            .join()
+            .unwrap();
+
+        assert_eq!(lock.owner(), Some(owner));
+        assert!(lock.check_thread().is_err());
+
+        let foreign = scope.spawn(|| lock.get().a).join();
+        assert!(foreign.is_err());
+    });
+}
+
+#[test]
+fn thread_lock_racing_init() {
+    let lock = ThreadLock::<Data>::new();
+
+    let initialized = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..8).map(|_| scope.spawn(|| lock.get_mut().a)).collect();
+        threads.into_iter().filter_map(|thread| thread.join().ok()).count()
+    });
+
+    assert_eq!(initialized, 1);
+}
+
+static RENDER: ThreadLock<Data> = ThreadLock::for_thread("render");
+
+#[test]
+fn thread_lock_for_named_thread() {
+    std::thread::Builder::new()
+        .name("render".into())
+        .spawn(|| {
+            assert!(RENDER.check_thread().is_ok());
+            assert_eq!(RENDER.get_or_init(|| Data { a: 3 }).a, 3);
+            assert_eq!(RENDER.take().unwrap().a, 3);
+            assert!(!RENDER.is_set());
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let err = RENDER.check_thread().unwrap_err();
+    assert_eq!(err.bound_to, "render");
+}
+
+#[test]
+#[should_panic(expected = "ThreadLock bound to thread audio was accessed from thread")]
+fn thread_lock_foreign_access() {
+    static AUDIO: ThreadLock<Data> = ThreadLock::for_thread("audio");
+    _ = AUDIO.try_get();
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        std::thread::park();
+    }
+}
+
+static DISPATCHED: MainLock<Data> = MainLock::new();
+
+#[test]
+#[serial]
+fn dispatch_from_worker() {
+    set_current_thread_as_main();
+    DISPATCHED.set(Data::default());
+
+    let worker = std::thread::spawn(|| {
+        DISPATCHED.dispatch(|data| data.a += 1);
+        block_on(DISPATCHED.dispatch_async(|data| {
+            data.a *= 2;
+            data.a
+        }))
+    });
+
+    while !worker.is_finished() {
+        pump();
+    }
+
+    assert_eq!(worker.join().unwrap(), 42);
+    assert_eq!(DISPATCHED.get().a, 42);
+    assert_eq!(pump(), 0);
+}
+
+#[test]
+#[serial]
+fn dispatch_on_main_thread_is_queued() {
+    set_current_thread_as_main();
+    DISPATCHED.set(Data::default());
+
+    DISPATCHED.dispatch(|data| {
+        data.a = 1;
+        DISPATCHED.dispatch(|data| data.a = 2);
+    });
+    assert_eq!(DISPATCHED.get().a, 20);
+
+    assert_eq!(pump(), 1);
+    assert_eq!(DISPATCHED.get().a, 1);
+    assert_eq!(pump(), 1);
+    assert_eq!(DISPATCHED.get().a, 2);
+}
+
+#[test]
+#[serial]
+#[should_panic(expected = "Task dispatched to main thread was dropped before completion")]
+fn dispatch_async_task_panicked() {
+    set_current_thread_as_main();
+    DISPATCHED.set(Data::default());
+
+    let future = DISPATCHED.dispatch_async(|_| -> i32 { panic!("task failed") });
+    assert!(std::panic::catch_unwind(pump).is_err());
+
+    block_on(future);
+}
+
+static DISPATCHED_MANUAL: MainLock<NonDefault> = MainLock::new();
+
+#[test]
+#[serial]
+fn dispatch_to_manual_lock() {
+    set_current_thread_as_main();
+
+    let unset = DISPATCHED_MANUAL.dispatch_async(|data| data._a);
+    assert_eq!(pump(), 1);
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| block_on(unset))).is_err());
+
+    DISPATCHED_MANUAL.set(NonDefault { _a: 1 });
+    DISPATCHED_MANUAL.dispatch(|data| data._a += 1);
+    let result = DISPATCHED_MANUAL.dispatch_async(|data| data._a * 10);
+    assert_eq!(pump(), 2);
+
+    assert_eq!(block_on(result), 20);
+    assert_eq!(DISPATCHED_MANUAL.take().unwrap()._a, 2);
+}
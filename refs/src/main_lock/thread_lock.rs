@@ -0,0 +1,243 @@
+use std::{
+    cell::{Cell, UnsafeCell},
+    fmt::{Display, Formatter},
+    sync::OnceLock,
+    thread::{self, ThreadId},
+};
+
+use crate::main_lock::{MainLockMut, MainLockRef, borrow::BorrowFlag};
+
+pub type ThreadLockRef<'a, T> = MainLockRef<'a, T>;
+pub type ThreadLockMut<'a, T> = MainLockMut<'a, T>;
+
+/// Returned by `ThreadLock::check_thread` when the lock is bound to another
+/// thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignThread {
+    pub bound_to: String,
+    pub current:  String,
+}
+
+impl Display for ForeignThread {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ThreadLock bound to thread {} was accessed from thread {}",
+            self.bound_to, self.current
+        )
+    }
+}
+
+impl std::error::Error for ForeignThread {}
+
+/// Same as `MainLock` but for any thread.
+///
+/// Lock created with `new` is bound to the thread which initializes it first.
+/// Lock created with `for_thread` can be used only from thread with given name.
+/// Access from other threads panics. Use `check_thread` to test it beforehand.
+pub struct ThreadLock<T> {
+    val:          UnsafeCell<Option<T>>,
+    thread_name:  Option<&'static str>,
+    owner:        OnceLock<ThreadId>,
+    initializing: Cell<bool>,
+    borrow:       BorrowFlag,
+}
+
+unsafe impl<T> Send for ThreadLock<T> {}
+unsafe impl<T> Sync for ThreadLock<T> {}
+
+impl<T> Default for ThreadLock<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ThreadLock<T> {
+    pub const fn new() -> Self {
+        Self {
+            val:          UnsafeCell::new(None),
+            thread_name:  None,
+            owner:        OnceLock::new(),
+            initializing: Cell::new(false),
+            borrow:       BorrowFlag::new(),
+        }
+    }
+
+    pub const fn for_thread(name: &'static str) -> Self {
+        Self {
+            val:          UnsafeCell::new(None),
+            thread_name:  Some(name),
+            owner:        OnceLock::new(),
+            initializing: Cell::new(false),
+            borrow:       BorrowFlag::new(),
+        }
+    }
+
+    /// Returns error if current thread can't access the lock.
+    /// Unbound lock can be accessed from any thread.
+    pub fn check_thread(&self) -> Result<(), ForeignThread> {
+        let current = thread::current();
+
+        if let Some(name) = self.thread_name {
+            return if current.name() == Some(name) {
+                Ok(())
+            } else {
+                Err(ForeignThread {
+                    bound_to: name.to_string(),
+                    current:  Self::describe(&current),
+                })
+            };
+        }
+
+        match self.owner.get() {
+            Some(owner) if *owner != current.id() => Err(ForeignThread {
+                bound_to: format!("{owner:?}"),
+                current:  Self::describe(&current),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn describe(thread: &thread::Thread) -> String {
+        thread.name().map_or_else(|| format!("{:?}", thread.id()), ToString::to_string)
+    }
+
+    /// Returns `false` if the lock is not bound yet.
+    /// Flags and value of unbound lock are not touched because other
+    /// threads can be binding it at the same time.
+    fn check(&self) -> bool {
+        if let Err(err) = self.check_thread() {
+            panic!("{err}");
+        }
+        if !self.is_bound_here() {
+            return false;
+        }
+        assert!(
+            !self.initializing.get(),
+            "ThreadLock<{}> was accessed from its own initializer",
+            std::any::type_name::<T>()
+        );
+        true
+    }
+
+    /// Binds the lock to current thread.
+    fn bind(&self) {
+        if let Err(err) = self.check_thread() {
+            panic!("{err}");
+        }
+        let current = thread::current().id();
+        let owner = *self.owner.get_or_init(|| current);
+        // Another thread could bind the lock after `check_thread`.
+        assert_eq!(owner, current, "ThreadLock was bound to another thread");
+    }
+
+    /// Returns `false` if the lock is not bound yet.
+    fn is_bound_here(&self) -> bool {
+        let Some(owner) = self.owner.get() else {
+            return false;
+        };
+        assert_eq!(
+            *owner,
+            thread::current().id(),
+            "ThreadLock was bound to another thread"
+        );
+        true
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn value(&self) -> Option<&mut T> {
+        unsafe { self.val.get().as_mut().unwrap().as_mut() }
+    }
+
+    fn value_ref(&self) -> Option<&T> {
+        unsafe { self.val.get().as_ref().unwrap().as_ref() }
+    }
+
+    /// Thread the lock is bound to. `None` until it is initialized.
+    pub fn owner(&self) -> Option<ThreadId> {
+        self.owner.get().copied()
+    }
+
+    pub fn get_or_init(&self, init: impl FnOnce() -> T) -> ThreadLockMut<'_, T> {
+        self.bind();
+        self.check();
+
+        if !self.is_set() {
+            struct Initializing<'a>(&'a Cell<bool>);
+
+            impl Drop for Initializing<'_> {
+                fn drop(&mut self) {
+                    self.0.set(false);
+                }
+            }
+
+            self.initializing.set(true);
+            let guard = Initializing(&self.initializing);
+            let value = init();
+            drop(guard);
+
+            return self.set(value);
+        }
+
+        let borrow = self.borrow.borrow_mut::<T>("ThreadLock");
+        MainLockMut::new(borrow, self.value().unwrap())
+    }
+
+    pub fn set(&self, value: T) -> ThreadLockMut<'_, T> {
+        self.bind();
+        self.check();
+        self.borrow.assert_unborrowed::<T>("ThreadLock");
+        let borrow = self.borrow.borrow_mut::<T>("ThreadLock");
+        let rf = unsafe { self.val.get().as_mut().unwrap() };
+        *rf = Some(value);
+        MainLockMut::new(borrow, rf.as_mut().unwrap())
+    }
+
+    pub fn is_set(&self) -> bool {
+        // Unbound lock was never initialized.
+        self.check() && self.value_ref().is_some()
+    }
+
+    pub fn try_get(&self) -> Option<ThreadLockRef<'_, T>> {
+        if !self.is_set() {
+            return None;
+        }
+        let borrow = self.borrow.borrow::<T>("ThreadLock");
+        self.value_ref().map(|val| MainLockRef::new(borrow, val))
+    }
+
+    pub fn try_get_mut(&self) -> Option<ThreadLockMut<'_, T>> {
+        if !self.is_set() {
+            return None;
+        }
+        let borrow = self.borrow.borrow_mut::<T>("ThreadLock");
+        self.value().map(|val| MainLockMut::new(borrow, val))
+    }
+
+    /// Takes the value out. The lock stays bound to its thread.
+    pub fn take(&self) -> Option<T> {
+        if !self.is_set() {
+            return None;
+        }
+        self.borrow.assert_unborrowed::<T>("ThreadLock");
+        unsafe { self.val.get().as_mut().unwrap() }.take()
+    }
+
+    pub fn reset(&self) {
+        drop(self.take());
+    }
+}
+
+impl<T: Default> ThreadLock<T> {
+    pub fn get(&self) -> ThreadLockRef<'_, T> {
+        if !self.is_set() {
+            drop(self.get_or_init(T::default));
+        }
+        let borrow = self.borrow.borrow::<T>("ThreadLock");
+        MainLockRef::new(borrow, self.value_ref().unwrap())
+    }
+
+    pub fn get_mut(&self) -> ThreadLockMut<'_, T> {
+        self.get_or_init(T::default)
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{
+        OnceLock,
+        mpsc::{self, Sender},
+    },
+};
+
+use crate::manage::SharedError;
+
+#[cfg(not(target_arch = "wasm32"))]
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Runs `job` on the worker thread shared by background loads of all managed
+/// types. Jobs are run one by one in the order they were sent.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn run_in_background(job: impl FnOnce() + Send + 'static) {
+    static WORKER: OnceLock<Sender<Job>> = OnceLock::new();
+
+    let worker = WORKER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+
+        std::thread::Builder::new()
+            .name("refs-background-loads".into())
+            .spawn(move || {
+                for job in receiver {
+                    // Panicking loader must not stop loads of other resources.
+                    if catch_unwind(AssertUnwindSafe(job)).is_err() {
+                        log::error!("Background load panicked");
+                    }
+                }
+            })
+            .expect("Failed to spawn background loads thread");
+
+        sender
+    });
+
+    worker.send(Box::new(job)).expect("Background loads thread stopped");
+}
+
+/// Value shown while the resource is loaded with `DataManager::get_async`.
+pub trait Placeholder {
+    fn placeholder() -> Self;
+}
+
+#[derive(Debug, Clone)]
+pub enum LoadStatus {
+    NotLoaded,
+    /// Placeholder is stored and the resource is being loaded on a worker.
+    Loading,
+    Loaded,
+    /// Background load failed. Placeholder is kept in storage.
+    Failed(SharedError),
+}
+
+impl LoadStatus {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        matches!(self, Self::Loaded)
+    }
+}
+
+struct Load {
+    /// Distinguishes loads of the same name started after the previous one
+    /// was freed or failed.
+    generation: u64,
+    status:     LoadStatus,
+}
+
+/// Background loads of a managed type. See `DataManager::get_async`.
+/// Only loading and failed names are stored.
+pub struct BackgroundLoads {
+    last_generation: u64,
+    loads:           BTreeMap<String, Load>,
+}
+
+impl BackgroundLoads {
+    pub const fn new() -> Self {
+        Self {
+            last_generation: 0,
+            loads:           BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.loads.values().filter(|load| load.status.is_loading()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn status(&self, name: &str) -> Option<LoadStatus> {
+        self.loads.get(name).map(|load| load.status.clone())
+    }
+
+    /// Returns generation of the started load.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub(crate) fn set_loading(&mut self, name: &str) -> u64 {
+        self.last_generation += 1;
+        self.loads.insert(
+            name.to_string(),
+            Load {
+                generation: self.last_generation,
+                status:     LoadStatus::Loading,
+            },
+        );
+        self.last_generation
+    }
+
+    /// Returns `false` if the load was freed or replaced by a newer one.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub(crate) fn is_current(&self, name: &str, generation: u64) -> bool {
+        self.loads.get(name).is_some_and(|load| load.generation == generation)
+    }
+
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub(crate) fn set_failed(&mut self, name: &str, error: SharedError) {
+        if let Some(load) = self.loads.get_mut(name) {
+            load.status = LoadStatus::Failed(error);
+        }
+    }
+
+    pub(crate) fn forget(&mut self, name: &str) {
+        self.loads.remove(name);
+    }
+}
+
+impl Default for BackgroundLoads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,15 +1,25 @@
 use std::{
     mem::transmute,
-    ops::Deref,
+    ops::{Deref, DerefMut},
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Result;
-use parking_lot::{RwLockReadGuard, RwLockWriteGuard};
+use hreads::is_main_thread;
+use parking_lot::{MutexGuard, RwLockReadGuard, RwLockWriteGuard};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::manage::{Placeholder, background::run_in_background};
 use crate::{
     Own, Weak,
-    manage::{DataStorage, Managed},
+    main_lock::dispatch_task,
+    manage::{
+        BackgroundLoads, DataStorage, Eviction, Fetcher, Handles, HotReload, LoadError, LoadStatus, Managed,
+        ManagedHandle, PendingLoads,
+        pending::{LoadRole, SharedError},
+    },
 };
 
 pub trait DataManager<T: Managed> {
@@ -18,13 +28,31 @@ pub trait DataManager<T: Managed> {
 
     fn storage() -> RwLockReadGuard<'static, DataStorage<T>>;
     fn storage_mut() -> RwLockWriteGuard<'static, DataStorage<T>>;
+    fn pending_loads() -> MutexGuard<'static, PendingLoads<T>>;
+    fn hot_reload() -> MutexGuard<'static, HotReload<T>>;
+    fn eviction() -> MutexGuard<'static, Eviction<T>>;
+    fn handles() -> MutexGuard<'static, Handles>;
+    fn background_loads() -> MutexGuard<'static, BackgroundLoads>;
+
+    fn fetcher() -> Arc<dyn Fetcher>;
+    fn set_fetcher(fetcher: impl Fetcher + 'static);
 
     fn full_path(name: &str) -> PathBuf {
         Self::root_path().join(name)
     }
 
+    /// Off main thread the value is removed from storage immediately and
+    /// dropped during next `main_lock::pump`.
     fn free_with_name(name: impl ToString) {
-        Self::storage_mut().remove(&name.to_string());
+        let name = name.to_string();
+        let removed = Self::storage_mut().remove(&name);
+        Self::hot_reload().forget(&name);
+        Self::eviction().removed(&name);
+        Self::background_loads().forget(&name);
+        Self::handles().forget(&name);
+        // Dropped after storage is unlocked because `Drop` of removed value could
+        // access it.
+        drop_on_main_thread(removed);
     }
 
     fn free(self: Weak<Self>) {
@@ -38,21 +66,21 @@ pub trait DataManager<T: Managed> {
             .expect("Failed to find managed object to free.")
             .0
             .clone();
-        storage.remove(&key);
+        let removed = storage.remove(&key);
+        drop(storage);
+        Self::hot_reload().forget(&key);
+        Self::eviction().removed(&key);
+        Self::background_loads().forget(&key);
+        Self::handles().forget(&key);
+        drop_on_main_thread(removed);
     }
 
     fn store_with_name<E>(name: &str, create: impl FnOnce() -> Result<T, E>) -> Result<Weak<T>, E> {
-        if let Some(entry) = Self::storage().get(name) {
-            return Ok(entry.weak());
+        if let Some(existing) = Self::get_existing(name) {
+            return Ok(existing);
         }
 
-        let entry = Own::new(create()?);
-
-        let weak = entry.weak();
-
-        Self::storage_mut().insert(name.to_owned(), entry);
-
-        Ok(weak)
+        Ok(insert(name.to_owned(), Own::new(create()?)))
     }
 
     unsafe fn get_static(self: Weak<Self>) -> &'static T {
@@ -68,45 +96,402 @@ pub trait DataManager<T: Managed> {
     }
 
     fn get_existing(name: impl ToString) -> Option<Weak<T>> {
-        Self::storage().get(&name.to_string()).map(Own::weak)
+        let name = name.to_string();
+        let existing = Self::storage().get(&name).map(Own::weak);
+        if existing.is_some() {
+            Self::eviction().touch(&name);
+        }
+        existing
     }
 
+    /// Panics if loading fails. Use `try_get` to handle errors.
     fn get(name: impl ToString) -> Weak<T> {
+        Self::try_get(name).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn try_get(name: impl ToString) -> Result<Weak<T>, LoadError> {
         let name = name.to_string();
 
-        if let Some(existing) = Self::storage().get(&name) {
-            return existing.weak();
+        if let Some(existing) = Self::get_existing(&name) {
+            return Ok(existing);
         }
 
-        let new = Own::new(T::load_path(&Self::full_path(&name)));
-        let weak = new.weak();
+        let path = Self::full_path(&name);
 
-        Self::storage_mut().insert(name, new);
+        let new = match T::try_load_path(&path) {
+            Ok(new) => Own::new(new),
+            Err(err) => {
+                return Err(LoadError {
+                    name,
+                    path: Some(path),
+                    source: err.into(),
+                });
+            }
+        };
+        Self::hot_reload().record(&name, &path);
 
-        weak
+        Ok(insert(name, new))
     }
 
+    /// Panics if loading fails. Use `try_load` to handle errors.
     fn load(data: &[u8], name: impl ToString) -> Weak<T> {
+        Self::try_load(data, name).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn try_load(data: &[u8], name: impl ToString) -> Result<Weak<T>, LoadError> {
         let name = name.to_string();
 
-        if let Some(existing) = Self::storage().get(&name) {
-            return existing.weak();
+        if let Some(existing) = Self::get_existing(&name) {
+            return Ok(existing);
         }
 
-        let new = Own::new(T::load_data(data, &name));
-        let weak = new.weak();
+        let new = match T::try_load_data(data, &name) {
+            Ok(new) => Own::new(new),
+            Err(err) => {
+                return Err(LoadError {
+                    name,
+                    path: None,
+                    source: err.into(),
+                });
+            }
+        };
+        Ok(insert(name, new))
+    }
+
+    /// Stores placeholder and loads the resource with
+    /// `TryResourceLoader::try_load_path` on a worker thread shared by all
+    /// background loads. Loaded value replaces placeholder in place during
+    /// `main_lock::pump` so returned `Weak` sees it. Progress is reported by
+    /// `load_status`.
+    ///
+    /// While loading `get` returns the placeholder. If loading fails the
+    /// placeholder is kept and calling `get_async` again retries the load.
+    /// Not available on wasm where threads can't be spawned.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn get_async(name: impl ToString) -> Weak<T>
+    where T: Placeholder + Send {
+        let name = name.to_string();
 
-        Self::storage_mut().insert(name, new);
+        let failed = matches!(
+            Self::background_loads().status(&name),
+            Some(LoadStatus::Failed(_))
+        );
+
+        let weak = match Self::get_existing(&name) {
+            Some(existing) if !failed => return existing,
+            Some(existing) => existing,
+            None => insert(name.clone(), Own::new(T::placeholder())),
+        };
+
+        let generation = Self::background_loads().set_loading(&name);
+
+        let path = Self::full_path(&name);
+
+        run_in_background(move || {
+            let result = T::try_load_path(&path).map_err(|err| SharedError::new(err.into()));
+            dispatch_task(move || finish_background_load(name, generation, &path, result));
+        });
 
         weak
     }
 
+    fn load_status(name: impl ToString) -> LoadStatus {
+        let name = name.to_string();
+
+        if let Some(status) = Self::background_loads().status(&name) {
+            return status;
+        }
+
+        if Self::storage().contains_key(&name) {
+            LoadStatus::Loaded
+        } else {
+            LoadStatus::NotLoaded
+        }
+    }
+
+    /// Like `get` but returns a counted handle. The resource is freed when the
+    /// last handle is dropped. Panics if loading fails.
+    fn handle(name: impl ToString) -> ManagedHandle<T> {
+        Self::try_handle(name).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn try_handle(name: impl ToString) -> Result<ManagedHandle<T>, LoadError> {
+        let name = name.to_string();
+        let weak = Self::try_get(&name)?;
+        Ok(ManagedHandle::new(&name, weak))
+    }
+
+    /// Keeps resources for `grace` after their last handle is dropped so they
+    /// are not reloaded if requested again shortly. Such resources are freed
+    /// by `release_expired`.
+    fn set_grace_period(grace: Duration) {
+        Self::handles().set_grace_period(grace);
+    }
+
+    /// Frees resources whose handles were dropped longer than grace period
+    /// ago. Should be called regularly, for example every frame.
+    /// Returns names of freed resources.
+    fn release_expired() -> Vec<String> {
+        let expired = Self::handles().take_expired();
+        for name in &expired {
+            Self::free_with_name(name);
+        }
+        expired
+    }
+
+    /// Pinned resources are never evicted. Can be called before the resource
+    /// is loaded.
+    fn pin(name: impl ToString) {
+        Self::eviction().pin(&name.to_string());
+    }
+
+    fn unpin(name: impl ToString) {
+        Self::eviction().unpin(&name.to_string());
+        Self::evict();
+    }
+
+    /// Frees resources exceeding `Eviction` budget. It is checked
+    /// automatically when resources are stored so this is needed only after
+    /// the budget is reduced. Resources inserted directly into `storage_mut`
+    /// are not tracked. Returns names of evicted resources.
+    fn evict() -> Vec<String> {
+        evict::<T>(None)
+    }
+
+    /// Starts watching files of resources loaded with `get` and `try_get`.
+    /// Changes are checked by `poll_reload` not more often than `interval`.
+    fn watch(interval: Duration) {
+        let storage = Self::storage();
+        let mut hot_reload = Self::hot_reload();
+
+        hot_reload.enable(interval);
+
+        for name in storage.keys() {
+            hot_reload.record(name, &Self::full_path(name));
+        }
+    }
+
+    fn unwatch() {
+        Self::hot_reload().disable();
+    }
+
+    /// Called after a resource is replaced by `reload_changed`.
+    fn on_reload(callback: impl FnMut(&str, Weak<T>) + Send + 'static) {
+        Self::hot_reload().subscribe(Box::new(callback));
+    }
+
+    /// Reloads changed resources if watch interval passed.
+    /// Should be called regularly from main thread, for example every frame.
+    fn poll_reload() -> Vec<String> {
+        if !Self::hot_reload().should_poll() {
+            return vec![];
+        }
+        Self::reload_changed()
+    }
+
+    /// Reloads resources whose files were modified with
+    /// `TryResourceLoader::try_load_path`. New values replace old ones in
+    /// place so existing `Weak`s see them. Resources which fail to load
+    /// keep old values. Returns names of reloaded resources.
+    ///
+    /// Can be called only from main thread.
+    fn reload_changed() -> Vec<String> {
+        let changed: Vec<(String, PathBuf)> = {
+            let storage = Self::storage();
+            let mut hot_reload = Self::hot_reload();
+
+            if !hot_reload.is_enabled() {
+                return vec![];
+            }
+
+            storage
+                .keys()
+                .map(|name| (name.clone(), Self::full_path(name)))
+                .filter(|(name, path)| hot_reload.check_modified(name, path))
+                .collect()
+        };
+
+        let mut reloaded = vec![];
+
+        for (name, path) in changed {
+            let new = match T::try_load_path(&path) {
+                Ok(new) => new,
+                Err(err) => {
+                    log::warn!("Failed to reload {name} from {}: {}", path.display(), err.into());
+                    continue;
+                }
+            };
+
+            let mut storage = Self::storage_mut();
+
+            // Could be freed during loading.
+            let Some(entry) = storage.get_mut(&name) else {
+                continue;
+            };
+
+            let old = std::mem::replace(entry.deref_mut(), new);
+            let weak = entry.weak();
+            Self::eviction().stored(&name, entry);
+
+            drop(storage);
+            drop(old);
+
+            // Reloaded value could be bigger than the old one.
+            evict::<T>(Some(&name));
+
+            reloaded.push((name, weak));
+        }
+
+        let mut callbacks = Self::hot_reload().take_callbacks();
+
+        for (name, weak) in &reloaded {
+            for callback in &mut callbacks {
+                callback(name, *weak);
+            }
+        }
+
+        Self::hot_reload().restore_callbacks(callbacks);
+
+        reloaded.into_iter().map(|(name, _)| name).collect()
+    }
+
+    /// Loads data returned by `fetch` with `TryResourceLoader::try_load_data`.
+    ///
+    /// If `name` is already being loaded `fetch` is not awaited and the caller
+    /// waits for the running load. Its result or error is returned to all
+    /// callers.
     #[allow(async_fn_in_trait)]
-    async fn download(name: impl ToString, url: &str) -> Result<Weak<T>> {
+    async fn load_async(
+        name: impl ToString,
+        fetch: impl Future<Output = Result<Vec<u8>>>,
+    ) -> Result<Weak<T>> {
         let name = name.to_string();
 
-        let data = reqwest::get(url).await?.bytes().await?;
+        if let Some(existing) = Self::get_existing(&name) {
+            return Ok(existing);
+        }
+
+        let role = Self::pending_loads().enter(&name, Self::pending_loads);
+
+        let guard = match role {
+            LoadRole::Leader(guard) => guard,
+            LoadRole::Waiter(load) => return Ok(load.await?),
+        };
+
+        // Previous load could finish before this one was registered.
+        if let Some(existing) = Self::get_existing(&name) {
+            guard.finish(Ok(existing));
+            return Ok(existing);
+        }
+
+        let result = fetch
+            .await
+            .and_then(|data| Ok(Self::try_load(&data, &name)?))
+            .map_err(SharedError::new);
 
-        Ok(Self::load(&data, &name))
+        guard.finish(result.clone());
+
+        Ok(result?)
     }
+
+    /// Downloads data with `Self::fetcher` and loads it with
+    /// `TryResourceLoader::try_load_data`.
+    #[allow(async_fn_in_trait)]
+    async fn download(name: impl ToString, url: &str) -> Result<Weak<T>> {
+        let fetcher = Self::fetcher();
+        Self::load_async(name, fetcher.fetch(url)).await
+    }
+}
+
+/// Stores loaded resource and evicts other resources if it exceeds the budget.
+fn insert<T: Managed>(name: String, entry: Own<T>) -> Weak<T> {
+    let weak = entry.weak();
+
+    T::eviction().stored(&name, &entry);
+    T::storage_mut().insert(name.clone(), entry);
+
+    evict::<T>(Some(&name));
+
+    weak
+}
+
+fn evict<T: Managed>(keep: Option<&str>) -> Vec<String> {
+    let victims = T::eviction().take_victims(keep);
+
+    if victims.is_empty() {
+        return victims;
+    }
+
+    let evicted: Vec<Own<T>> = {
+        let mut storage = T::storage_mut();
+        victims.iter().filter_map(|name| storage.remove(name)).collect()
+    };
+
+    let mut hot_reload = T::hot_reload();
+    let mut handles = T::handles();
+    for name in &victims {
+        hot_reload.forget(name);
+        handles.forget(name);
+    }
+    drop((hot_reload, handles));
+
+    // Dropped after storage is unlocked because `Drop` of evicted values could
+    // access it.
+    drop_on_main_thread(evicted);
+
+    victims
+}
+
+/// `Own` can be dropped only on main thread so values freed on other threads,
+/// for example by loads or dropped `ManagedHandle`s, are dropped during next
+/// `main_lock::pump`.
+fn drop_on_main_thread(value: impl Send + 'static) {
+    if is_main_thread() {
+        drop(value);
+    } else {
+        dispatch_task(move || drop(value));
+    }
+}
+
+/// Replaces placeholder with the value loaded by `DataManager::get_async`.
+/// Results of loads which were freed or restarted are ignored.
+#[cfg(not(target_arch = "wasm32"))]
+fn finish_background_load<T: Managed>(
+    name: String,
+    generation: u64,
+    path: &Path,
+    result: Result<T, SharedError>,
+) {
+    if !T::background_loads().is_current(&name, generation) {
+        return;
+    }
+
+    let new = match result {
+        Ok(new) => new,
+        Err(err) => {
+            log::warn!("Failed to load {name} from {}: {err}", path.display());
+            T::background_loads().set_failed(&name, err);
+            return;
+        }
+    };
+
+    T::background_loads().forget(&name);
+
+    let mut storage = T::storage_mut();
+
+    // Placeholder could be freed or evicted during loading.
+    let Some(entry) = storage.get_mut(&name) else {
+        return;
+    };
+
+    let placeholder = std::mem::replace(entry.deref_mut(), new);
+    T::eviction().stored(&name, entry);
+
+    drop(storage);
+    drop(placeholder);
+
+    T::hot_reload().record(&name, path);
+
+    // Loaded value could be bigger than the placeholder.
+    evict::<T>(Some(&name));
 }
@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Evicts least recently used resources first.
+    #[default]
+    Lru,
+    /// Evicts least frequently used resources first.
+    Lfu,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Usage {
+    last_used: u64,
+    uses:      u64,
+    bytes:     usize,
+}
+
+/// Eviction state of a managed type. Budgets are not limited by default.
+///
+/// Configured with `DataManager::eviction`:
+/// `Texture::eviction().set_max_entries(Some(64))`.
+pub struct Eviction<T> {
+    policy:      EvictionPolicy,
+    max_entries: Option<usize>,
+    max_bytes:   Option<usize>,
+    size:        Option<fn(&T) -> usize>,
+    tick:        u64,
+    total_bytes: usize,
+    usage:       BTreeMap<String, Usage>,
+    pinned:      BTreeSet<String>,
+    /// Names with live `ManagedHandle`s.
+    in_use:      BTreeSet<String>,
+}
+
+impl<T> Eviction<T> {
+    pub const fn new() -> Self {
+        Self {
+            policy:      EvictionPolicy::Lru,
+            max_entries: None,
+            max_bytes:   None,
+            size:        None,
+            tick:        0,
+            total_bytes: 0,
+            usage:       BTreeMap::new(),
+            pinned:      BTreeSet::new(),
+            in_use:      BTreeSet::new(),
+        }
+    }
+
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: EvictionPolicy) -> &mut Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn set_max_entries(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_entries = max;
+        self
+    }
+
+    /// Byte budget is checked using sizes reported by `size` for stored values.
+    /// Values stored before `size` was set count as 0 bytes.
+    pub fn set_max_bytes(&mut self, max: Option<usize>, size: fn(&T) -> usize) -> &mut Self {
+        self.max_bytes = max;
+        self.size = Some(size);
+        self
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.total_bytes
+    }
+
+    pub fn is_pinned(&self, name: &str) -> bool {
+        self.pinned.contains(name)
+    }
+
+    pub(crate) fn pin(&mut self, name: &str) {
+        self.pinned.insert(name.to_string());
+    }
+
+    pub(crate) fn unpin(&mut self, name: &str) {
+        self.pinned.remove(name);
+    }
+
+    pub(crate) fn set_in_use(&mut self, name: &str, in_use: bool) {
+        if in_use {
+            self.in_use.insert(name.to_string());
+        } else {
+            self.in_use.remove(name);
+        }
+    }
+
+    pub(crate) fn touch(&mut self, name: &str) {
+        self.tick += 1;
+        if let Some(usage) = self.usage.get_mut(name) {
+            usage.last_used = self.tick;
+            usage.uses += 1;
+        }
+    }
+
+    /// Starts tracking stored value or updates its size if it was replaced.
+    pub(crate) fn stored(&mut self, name: &str, value: &T) {
+        self.tick += 1;
+
+        let bytes = self.size.map_or(0, |size| size(value));
+
+        let usage = self.usage.entry(name.to_string()).or_insert(Usage {
+            last_used: self.tick,
+            uses:      0,
+            bytes:     0,
+        });
+
+        self.total_bytes = self.total_bytes - usage.bytes + bytes;
+        usage.bytes = bytes;
+        usage.last_used = self.tick;
+        usage.uses += 1;
+    }
+
+    pub(crate) fn removed(&mut self, name: &str) {
+        if let Some(usage) = self.usage.remove(name) {
+            self.total_bytes -= usage.bytes;
+        }
+    }
+
+    fn over_budget(&self) -> bool {
+        self.max_entries.is_some_and(|max| self.usage.len() > max)
+            || self.max_bytes.is_some_and(|max| self.total_bytes > max)
+    }
+
+    /// Returns names which have to be evicted to fit into the budget.
+    /// Pinned resources, resources with live handles and `keep` are never
+    /// evicted.
+    pub(crate) fn take_victims(&mut self, keep: Option<&str>) -> Vec<String> {
+        let mut victims = vec![];
+
+        while self.over_budget() {
+            let victim = self
+                .usage
+                .iter()
+                .filter(|(name, _)| {
+                    Some(name.as_str()) != keep
+                        && !self.pinned.contains(*name)
+                        && !self.in_use.contains(*name)
+                })
+                .min_by_key(|(_, usage)| match self.policy {
+                    EvictionPolicy::Lru => (usage.last_used, 0),
+                    EvictionPolicy::Lfu => (usage.uses, usage.last_used),
+                })
+                .map(|(name, _)| name.clone());
+
+            let Some(victim) = victim else {
+                break;
+            };
+
+            self.removed(&victim);
+            victims.push(victim);
+        }
+
+        victims
+    }
+}
+
+impl<T> Default for Eviction<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
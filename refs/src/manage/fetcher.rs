@@ -0,0 +1,80 @@
+use std::{collections::HashMap, future::Future, path::Path, pin::Pin, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use parking_lot::RwLock;
+
+pub type FetchFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + 'a>>;
+
+/// Transport used by `DataManager::download`.
+/// Can be set per managed type with `DataManager::set_fetcher`.
+pub trait Fetcher: Send + Sync {
+    fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a>;
+}
+
+/// Fetcher used when none is set: `ReqwestFetcher` with `reqwest` feature
+/// and `FileFetcher` without it.
+pub fn default_fetcher() -> Arc<dyn Fetcher> {
+    #[cfg(feature = "reqwest")]
+    return Arc::new(ReqwestFetcher);
+    #[cfg(not(feature = "reqwest"))]
+    return Arc::new(FileFetcher);
+}
+
+#[cfg(feature = "reqwest")]
+pub struct ReqwestFetcher;
+
+#[cfg(feature = "reqwest")]
+impl Fetcher for ReqwestFetcher {
+    fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a> {
+        Box::pin(async move { Ok(reqwest::get(url).await?.error_for_status()?.bytes().await?.to_vec()) })
+    }
+}
+
+/// Reads local files. Accepts `file://` urls and plain paths.
+pub struct FileFetcher;
+
+impl Fetcher for FileFetcher {
+    fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a> {
+        Box::pin(async move {
+            let path = Path::new(url.strip_prefix("file://").unwrap_or(url));
+            std::fs::read(path).map_err(|err| anyhow!("Failed to read {}: {err}", path.display()))
+        })
+    }
+}
+
+/// Returns data stored for exact url. Useful for tests.
+#[derive(Default)]
+pub struct MemoryFetcher {
+    data: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(self, url: impl ToString, data: impl Into<Vec<u8>>) -> Self {
+        self.insert(url, data);
+        self
+    }
+
+    pub fn insert(&self, url: impl ToString, data: impl Into<Vec<u8>>) {
+        self.data.write().insert(url.to_string(), data.into());
+    }
+
+    pub fn remove(&self, url: &str) -> Option<Vec<u8>> {
+        self.data.write().remove(url)
+    }
+}
+
+impl Fetcher for MemoryFetcher {
+    fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a> {
+        Box::pin(async move {
+            self.data
+                .read()
+                .get(url)
+                .cloned()
+                .ok_or_else(|| anyhow!("No data for url: {url}"))
+        })
+    }
+}
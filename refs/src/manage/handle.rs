@@ -0,0 +1,179 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Formatter},
+    ops::Deref,
+    sync::Arc,
+    time::Duration,
+};
+
+use instant::Instant;
+
+use crate::{Weak, manage::Managed};
+
+#[derive(Debug)]
+struct Users {
+    count:    usize,
+    /// When the last handle was dropped.
+    released: Option<Instant>,
+}
+
+/// Handle counts of a managed type. See `DataManager::handle`.
+pub struct Handles {
+    grace: Duration,
+    users: BTreeMap<String, Users>,
+}
+
+impl Handles {
+    pub const fn new() -> Self {
+        Self {
+            grace: Duration::ZERO,
+            users: BTreeMap::new(),
+        }
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        self.grace
+    }
+
+    pub fn count(&self, name: &str) -> usize {
+        self.users.get(name).map_or(0, |users| users.count)
+    }
+
+    pub(crate) fn set_grace_period(&mut self, grace: Duration) {
+        self.grace = grace;
+    }
+
+    /// Returns `true` for the first handle.
+    pub(crate) fn acquire(&mut self, name: &str) -> bool {
+        let users = self.users.entry(name.to_string()).or_insert(Users {
+            count:    0,
+            released: None,
+        });
+        users.count += 1;
+        users.released = None;
+        users.count == 1
+    }
+
+    /// Returns `true` for the last handle.
+    pub(crate) fn release(&mut self, name: &str) -> bool {
+        let Some(users) = self.users.get_mut(name) else {
+            return false;
+        };
+
+        users.count -= 1;
+
+        if users.count > 0 {
+            return false;
+        }
+
+        if self.grace.is_zero() {
+            self.users.remove(name);
+        } else {
+            users.released = Some(Instant::now());
+        }
+
+        true
+    }
+
+    /// Stops tracking `name` when its resource is removed from storage.
+    pub(crate) fn forget(&mut self, name: &str) {
+        self.users.remove(name);
+    }
+
+    /// Returns released names whose grace period has passed and stops
+    /// tracking them.
+    pub(crate) fn take_expired(&mut self) -> Vec<String> {
+        let expired: Vec<String> = self
+            .users
+            .iter()
+            .filter(|(_, users)| users.released.is_some_and(|released| released.elapsed() >= self.grace))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in &expired {
+            self.users.remove(name);
+        }
+
+        expired
+    }
+}
+
+impl Default for Handles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Counted reference to a managed resource. Storage releases the resource
+/// when the last handle of its name is dropped, or after
+/// `DataManager::set_grace_period` if it is set.
+///
+/// Resources accessed only with `get` are not affected. If the last handle is
+/// dropped off main thread the resource is dropped during next
+/// `main_lock::pump`.
+pub struct ManagedHandle<T: Managed> {
+    name: Arc<str>,
+    weak: Weak<T>,
+}
+
+impl<T: Managed> ManagedHandle<T> {
+    pub(crate) fn new(name: &str, weak: Weak<T>) -> Self {
+        if T::handles().acquire(name) {
+            T::eviction().set_in_use(name, true);
+        }
+
+        Self {
+            name: name.into(),
+            weak,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn weak(&self) -> Weak<T> {
+        self.weak
+    }
+}
+
+impl<T: Managed> Clone for ManagedHandle<T> {
+    fn clone(&self) -> Self {
+        Self::new(&self.name, self.weak)
+    }
+}
+
+impl<T: Managed> Deref for ManagedHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.weak
+    }
+}
+
+impl<T: Managed> Debug for ManagedHandle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ManagedHandle")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Managed> Drop for ManagedHandle<T> {
+    fn drop(&mut self) {
+        let mut handles = T::handles();
+
+        if !handles.release(&self.name) {
+            return;
+        }
+
+        let free = handles.grace_period().is_zero();
+        drop(handles);
+
+        T::eviction().set_in_use(&self.name, false);
+
+        if free {
+            T::free_with_name(&*self.name);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use instant::Instant;
+
+use crate::Weak;
+
+type ReloadCallback<T> = Box<dyn FnMut(&str, Weak<T>) + Send>;
+
+/// Hot reload state of a managed type. See `DataManager::watch`.
+pub struct HotReload<T> {
+    enabled:   bool,
+    interval:  Duration,
+    last_poll: Option<Instant>,
+    /// Modification times of files backing stored names.
+    mtimes:    BTreeMap<String, SystemTime>,
+    callbacks: Vec<ReloadCallback<T>>,
+}
+
+impl<T> HotReload<T> {
+    pub const fn new() -> Self {
+        Self {
+            enabled:   false,
+            interval:  Duration::ZERO,
+            last_poll: None,
+            mtimes:    BTreeMap::new(),
+            callbacks: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn enable(&mut self, interval: Duration) {
+        self.enabled = true;
+        self.interval = interval;
+        self.last_poll = None;
+    }
+
+    pub(crate) fn disable(&mut self) {
+        self.enabled = false;
+        self.mtimes.clear();
+    }
+
+    /// Remembers modification time of the file `name` was loaded from.
+    pub(crate) fn record(&mut self, name: &str, path: &Path) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(mtime) = modified(path) {
+            self.mtimes.insert(name.to_string(), mtime);
+        }
+    }
+
+    pub(crate) fn forget(&mut self, name: &str) {
+        self.mtimes.remove(name);
+    }
+
+    /// Returns `false` if `interval` didn't pass since the last poll.
+    pub(crate) fn should_poll(&mut self) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if self.last_poll.is_some_and(|last| last.elapsed() < self.interval) {
+            return false;
+        }
+        self.last_poll = Some(Instant::now());
+        true
+    }
+
+    /// Returns `true` if the file was modified since it was recorded.
+    /// Updates recorded time.
+    pub(crate) fn check_modified(&mut self, name: &str, path: &Path) -> bool {
+        let Some(mtime) = modified(path) else {
+            return false;
+        };
+
+        match self.mtimes.insert(name.to_string(), mtime) {
+            Some(previous) => previous != mtime,
+            None => false,
+        }
+    }
+
+    pub(crate) fn subscribe(&mut self, callback: ReloadCallback<T>) {
+        self.callbacks.push(callback);
+    }
+
+    pub(crate) fn take_callbacks(&mut self) -> Vec<ReloadCallback<T>> {
+        std::mem::take(&mut self.callbacks)
+    }
+
+    /// Returns callbacks taken with `take_callbacks` keeping ones added
+    /// meanwhile.
+    pub(crate) fn restore_callbacks(&mut self, mut callbacks: Vec<ReloadCallback<T>>) {
+        callbacks.append(&mut self.callbacks);
+        self.callbacks = callbacks;
+    }
+}
+
+impl<T> Default for HotReload<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,39 @@
+use std::{
+    error::Error,
+    fmt::{Debug, Display, Formatter},
+    path::PathBuf,
+};
+
+/// Error returned by `DataManager::try_get` and `DataManager::try_load`.
+pub struct LoadError {
+    pub name:   String,
+    /// `None` if the resource was loaded from data.
+    pub path:   Option<PathBuf>,
+    pub source: anyhow::Error,
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to load {}", self.name)?;
+        if let Some(path) = &self.path {
+            write!(f, " from {}", path.display())?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+impl Debug for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadError")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
@@ -4,6 +4,18 @@ macro_rules! managed {
         static __MANAGED_ROOT_PATH: std::sync::OnceLock<std::path::PathBuf> = std::sync::OnceLock::new();
         static __STORAGE: $($refs_path)::+::__internal_deps::RwLock<$($refs_path)::+::manage::DataStorage<$type>> =
             $($refs_path)::+::__internal_deps::RwLock::new(std::collections::BTreeMap::new());
+        static __PENDING_LOADS: $($refs_path)::+::__internal_deps::Mutex<$($refs_path)::+::manage::PendingLoads<$type>> =
+            $($refs_path)::+::__internal_deps::Mutex::new($($refs_path)::+::manage::PendingLoads::new());
+        static __FETCHER: $($refs_path)::+::__internal_deps::RwLock<Option<std::sync::Arc<dyn $($refs_path)::+::manage::Fetcher>>> =
+            $($refs_path)::+::__internal_deps::RwLock::new(None);
+        static __HOT_RELOAD: $($refs_path)::+::__internal_deps::Mutex<$($refs_path)::+::manage::HotReload<$type>> =
+            $($refs_path)::+::__internal_deps::Mutex::new($($refs_path)::+::manage::HotReload::new());
+        static __EVICTION: $($refs_path)::+::__internal_deps::Mutex<$($refs_path)::+::manage::Eviction<$type>> =
+            $($refs_path)::+::__internal_deps::Mutex::new($($refs_path)::+::manage::Eviction::new());
+        static __HANDLES: $($refs_path)::+::__internal_deps::Mutex<$($refs_path)::+::manage::Handles> =
+            $($refs_path)::+::__internal_deps::Mutex::new($($refs_path)::+::manage::Handles::new());
+        static __BACKGROUND_LOADS: $($refs_path)::+::__internal_deps::Mutex<$($refs_path)::+::manage::BackgroundLoads> =
+            $($refs_path)::+::__internal_deps::Mutex::new($($refs_path)::+::manage::BackgroundLoads::new());
 
         impl $($refs_path)::+::manage::Managed for $type {}
 
@@ -34,6 +46,34 @@ macro_rules! managed {
             fn storage_mut() -> $($refs_path)::+::__internal_deps::RwLockWriteGuard<'static, $($refs_path)::+::manage::DataStorage<$type>> {
                 __STORAGE.write()
             }
+
+            fn pending_loads() -> $($refs_path)::+::__internal_deps::MutexGuard<'static, $($refs_path)::+::manage::PendingLoads<$type>> {
+                __PENDING_LOADS.lock()
+            }
+
+            fn hot_reload() -> $($refs_path)::+::__internal_deps::MutexGuard<'static, $($refs_path)::+::manage::HotReload<$type>> {
+                __HOT_RELOAD.lock()
+            }
+
+            fn eviction() -> $($refs_path)::+::__internal_deps::MutexGuard<'static, $($refs_path)::+::manage::Eviction<$type>> {
+                __EVICTION.lock()
+            }
+
+            fn handles() -> $($refs_path)::+::__internal_deps::MutexGuard<'static, $($refs_path)::+::manage::Handles> {
+                __HANDLES.lock()
+            }
+
+            fn background_loads() -> $($refs_path)::+::__internal_deps::MutexGuard<'static, $($refs_path)::+::manage::BackgroundLoads> {
+                __BACKGROUND_LOADS.lock()
+            }
+
+            fn fetcher() -> std::sync::Arc<dyn $($refs_path)::+::manage::Fetcher> {
+                __FETCHER.read().clone().unwrap_or_else($($refs_path)::+::manage::default_fetcher)
+            }
+
+            fn set_fetcher(fetcher: impl $($refs_path)::+::manage::Fetcher + 'static) {
+                *__FETCHER.write() = Some(std::sync::Arc::new(fetcher));
+            }
         }
     };
 
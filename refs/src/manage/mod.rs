@@ -1,14 +1,31 @@
 use std::collections::BTreeMap;
 
+mod background;
 mod data_manager;
+mod eviction;
 mod exists_managed;
+mod fetcher;
+mod handle;
+mod hot_reload;
+mod load_error;
 mod managed;
+mod pending;
 mod resource_loader;
+mod tests;
 
+pub use background::{BackgroundLoads, LoadStatus, Placeholder};
 pub use data_manager::DataManager;
+pub use eviction::{Eviction, EvictionPolicy};
 pub use exists_managed::ExistsManaged;
-pub use resource_loader::ResourceLoader;
+#[cfg(feature = "reqwest")]
+pub use fetcher::ReqwestFetcher;
+pub use fetcher::{FetchFuture, Fetcher, FileFetcher, MemoryFetcher, default_fetcher};
+pub use handle::{Handles, ManagedHandle};
+pub use hot_reload::HotReload;
+pub use load_error::LoadError;
+pub use pending::{PendingLoads, SharedError};
+pub use resource_loader::{ResourceLoader, TryResourceLoader};
 
 pub type DataStorage<T> = BTreeMap<String, crate::Own<T>>;
 
-pub trait Managed: 'static + ResourceLoader + DataManager<Self> {}
+pub trait Managed: 'static + TryResourceLoader + DataManager<Self> {}
@@ -0,0 +1,202 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Debug, Display, Formatter},
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use parking_lot::{Mutex, MutexGuard};
+
+use crate::Weak;
+
+/// Error of a load shared by all callers waiting for it.
+#[derive(Clone)]
+pub struct SharedError(Arc<anyhow::Error>);
+
+impl SharedError {
+    pub(crate) fn new(error: anyhow::Error) -> Self {
+        Self(Arc::new(error))
+    }
+}
+
+impl Debug for SharedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&*self.0, f)
+    }
+}
+
+impl Display for SharedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl Error for SharedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+type LoadResult<T> = Result<Weak<T>, SharedError>;
+
+struct State<T> {
+    result:      Option<LoadResult<T>>,
+    /// Latest waker of each waiter by its id.
+    wakers:      BTreeMap<u64, Waker>,
+    last_waiter: u64,
+}
+
+/// Loads of a managed type which are in progress.
+pub struct PendingLoads<T> {
+    loads: BTreeMap<String, Arc<Mutex<State<T>>>>,
+}
+
+impl<T> PendingLoads<T> {
+    pub const fn new() -> Self {
+        Self {
+            loads: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_loading(&self, name: &str) -> bool {
+        self.loads.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.loads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.loads.is_empty()
+    }
+}
+
+impl<T: 'static> PendingLoads<T> {
+    /// Joins load of `name` if it is in progress. Otherwise registers new load
+    /// which is completed when returned guard is finished or dropped.
+    pub(crate) fn enter(
+        &mut self,
+        name: &str,
+        pending: fn() -> MutexGuard<'static, PendingLoads<T>>,
+    ) -> LoadRole<T> {
+        if let Some(state) = self.loads.get(name) {
+            let id = {
+                let mut state = state.lock();
+                state.last_waiter += 1;
+                state.last_waiter
+            };
+            return LoadRole::Waiter(PendingLoad {
+                id,
+                state: state.clone(),
+            });
+        }
+
+        let state = Arc::new(Mutex::new(State {
+            result:      None,
+            wakers:      BTreeMap::new(),
+            last_waiter: 0,
+        }));
+        self.loads.insert(name.to_string(), state.clone());
+
+        LoadRole::Leader(LoadGuard {
+            name: name.to_string(),
+            state,
+            pending,
+        })
+    }
+}
+
+impl<T> Default for PendingLoads<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) enum LoadRole<T: 'static> {
+    /// Performs the load.
+    Leader(LoadGuard<T>),
+    /// Waits for a load started by another caller.
+    Waiter(PendingLoad<T>),
+}
+
+/// Waits for a load started by another caller.
+pub(crate) struct PendingLoad<T> {
+    id:    u64,
+    state: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Future for PendingLoad<T> {
+    type Output = LoadResult<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock();
+
+        if let Some(result) = &state.result {
+            return Poll::Ready(result.clone());
+        }
+
+        // `clone_from` keeps the stored waker if it `will_wake` the same task.
+        state
+            .wakers
+            .entry(self.id)
+            .and_modify(|waker| waker.clone_from(cx.waker()))
+            .or_insert_with(|| cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for PendingLoad<T> {
+    fn drop(&mut self) {
+        self.state.lock().wakers.remove(&self.id);
+    }
+}
+
+/// Held by the caller performing the load.
+/// If it is dropped without `finish` waiters receive cancellation error.
+pub(crate) struct LoadGuard<T: 'static> {
+    name:    String,
+    state:   Arc<Mutex<State<T>>>,
+    pending: fn() -> MutexGuard<'static, PendingLoads<T>>,
+}
+
+impl<T: 'static> LoadGuard<T> {
+    pub(crate) fn finish(self, result: LoadResult<T>) {
+        self.complete(|| result);
+    }
+
+    fn complete(&self, result: impl FnOnce() -> LoadResult<T>) {
+        let wakers = {
+            let mut state = self.state.lock();
+            if state.result.is_some() {
+                return;
+            }
+            state.result = Some(result());
+            std::mem::take(&mut state.wakers)
+        };
+
+        let mut pending = (self.pending)();
+        if pending
+            .loads
+            .get(&self.name)
+            .is_some_and(|state| Arc::ptr_eq(state, &self.state))
+        {
+            pending.loads.remove(&self.name);
+        }
+        drop(pending);
+
+        for waker in wakers.into_values() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: 'static> Drop for LoadGuard<T> {
+    fn drop(&mut self) {
+        let name = &self.name;
+        self.complete(|| Err(SharedError::new(anyhow::anyhow!("Load of {name} was cancelled"))));
+    }
+}
@@ -1,6 +1,26 @@
-use std::path::Path;
+use std::{convert::Infallible, path::Path};
 
 pub trait ResourceLoader: Sized {
     fn load_path(path: &Path) -> Self;
     fn load_data(data: &[u8], name: impl ToString) -> Self;
 }
+
+/// Fallible version of `ResourceLoader`. Implemented for all `ResourceLoader`s.
+pub trait TryResourceLoader: Sized {
+    type Error: Into<anyhow::Error>;
+
+    fn try_load_path(path: &Path) -> Result<Self, Self::Error>;
+    fn try_load_data(data: &[u8], name: &str) -> Result<Self, Self::Error>;
+}
+
+impl<T: ResourceLoader> TryResourceLoader for T {
+    type Error = Infallible;
+
+    fn try_load_path(path: &Path) -> Result<Self, Self::Error> {
+        Ok(Self::load_path(path))
+    }
+
+    fn try_load_data(data: &[u8], name: &str) -> Result<Self, Self::Error> {
+        Ok(Self::load_data(data, name))
+    }
+}
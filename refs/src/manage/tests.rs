@@ -0,0 +1,886 @@
+#![cfg(test)]
+
+use std::{
+    cell::{Cell, RefCell},
+    future::{Future, poll_fn},
+    path::Path,
+    pin::pin,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use anyhow::{Result, anyhow};
+use hreads::set_current_thread_as_main;
+use serial_test::serial;
+
+use crate::manage::{DataManager, Fetcher, FileFetcher, MemoryFetcher, TryResourceLoader};
+
+struct Text {
+    text: String,
+}
+
+impl TryResourceLoader for Text {
+    type Error = anyhow::Error;
+
+    fn try_load_path(path: &Path) -> Result<Self> {
+        Self::try_load_data(&std::fs::read(path)?, "")
+    }
+
+    fn try_load_data(data: &[u8], _name: &str) -> Result<Self> {
+        Ok(Self {
+            text: String::from_utf8(data.to_vec())?,
+        })
+    }
+}
+
+crate::managed!(refs, Text);
+
+/// Declares managed `i32` newtype read from text files and `root()` which
+/// sets its root path to `dir` in temp directory.
+macro_rules! number_fixture {
+    ($name:ident, $dir:literal) => {
+        struct $name(i32);
+
+        impl crate::manage::TryResourceLoader for $name {
+            type Error = anyhow::Error;
+
+            fn try_load_path(path: &std::path::Path) -> anyhow::Result<Self> {
+                Self::try_load_data(&std::fs::read(path)?, "")
+            }
+
+            fn try_load_data(data: &[u8], _name: &str) -> anyhow::Result<Self> {
+                Ok(Self(String::from_utf8_lossy(data).trim().parse()?))
+            }
+        }
+
+        crate::managed!(refs, $name);
+
+        fn root() -> std::path::PathBuf {
+            static SET_ROOT: std::sync::Once = std::sync::Once::new();
+            let root = std::env::temp_dir().join($dir);
+            std::fs::create_dir_all(&root).unwrap();
+            SET_ROOT.call_once(|| <$name as crate::manage::DataManager<$name>>::set_root_path(&root));
+            root
+        }
+    };
+}
+
+type FetchResult = Rc<RefCell<Option<Result<Vec<u8>>>>>;
+
+/// Fetch which is pending until result is set.
+fn fetch(result: &FetchResult, calls: &Rc<Cell<usize>>) -> impl Future<Output = Result<Vec<u8>>> {
+    let result = result.clone();
+    let calls = calls.clone();
+    let mut called = false;
+
+    poll_fn(move |_| {
+        if !called {
+            called = true;
+            calls.set(calls.get() + 1);
+        }
+        result.borrow_mut().take().map_or(Poll::Pending, Poll::Ready)
+    })
+}
+
+fn poll<F: Future>(future: std::pin::Pin<&mut F>) -> Poll<F::Output> {
+    future.poll(&mut Context::from_waker(Waker::noop()))
+}
+
+#[test]
+#[serial]
+fn concurrent_loads_are_deduplicated() {
+    set_current_thread_as_main();
+
+    let result = FetchResult::default();
+    let calls = Rc::new(Cell::new(0));
+
+    let mut first = pin!(Text::load_async("dedup", fetch(&result, &calls)));
+    let mut second = pin!(Text::load_async("dedup", fetch(&result, &calls)));
+
+    assert!(poll(first.as_mut()).is_pending());
+    assert!(poll(second.as_mut()).is_pending());
+    assert!(Text::pending_loads().is_loading("dedup"));
+
+    *result.borrow_mut() = Some(Ok(b"hello".to_vec()));
+
+    let Poll::Ready(Ok(first)) = poll(first.as_mut()) else {
+        panic!("first load is not finished");
+    };
+    let Poll::Ready(Ok(second)) = poll(second.as_mut()) else {
+        panic!("second load is not finished");
+    };
+
+    assert_eq!(calls.get(), 1);
+    assert_eq!(first.text, "hello");
+    assert_eq!(first.addr(), second.addr());
+    assert!(Text::pending_loads().is_empty());
+
+    Text::free_with_name("dedup");
+}
+
+#[test]
+#[serial]
+fn load_error_is_shared() {
+    set_current_thread_as_main();
+
+    let result = FetchResult::default();
+    let calls = Rc::new(Cell::new(0));
+
+    let mut first = pin!(Text::load_async("error", fetch(&result, &calls)));
+    let mut second = pin!(Text::load_async("error", fetch(&result, &calls)));
+
+    assert!(poll(first.as_mut()).is_pending());
+    assert!(poll(second.as_mut()).is_pending());
+
+    *result.borrow_mut() = Some(Err(anyhow!("not found")));
+
+    let Poll::Ready(Err(first)) = poll(first.as_mut()) else {
+        panic!("first load didn't fail");
+    };
+    let Poll::Ready(Err(second)) = poll(second.as_mut()) else {
+        panic!("second load didn't fail");
+    };
+
+    assert_eq!(first.to_string(), "not found");
+    assert_eq!(second.to_string(), "not found");
+    assert!(Text::get_existing("error").is_none());
+    assert!(Text::pending_loads().is_empty());
+}
+
+#[test]
+#[serial]
+fn cancelled_load_notifies_waiters() {
+    set_current_thread_as_main();
+
+    let result = FetchResult::default();
+    let calls = Rc::new(Cell::new(0));
+
+    let mut second = Box::pin(Text::load_async("cancel", fetch(&result, &calls)));
+
+    {
+        let mut first = pin!(Text::load_async("cancel", fetch(&result, &calls)));
+        assert!(poll(first.as_mut()).is_pending());
+        assert!(poll(second.as_mut()).is_pending());
+    }
+
+    let Poll::Ready(Err(err)) = poll(second.as_mut()) else {
+        panic!("waiter was not notified");
+    };
+
+    assert_eq!(err.to_string(), "Load of cancel was cancelled");
+    assert!(Text::pending_loads().is_empty());
+}
+
+struct CountWakes(AtomicUsize);
+
+impl Wake for CountWakes {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+#[serial]
+fn waiter_keeps_one_waker() {
+    set_current_thread_as_main();
+
+    let result = FetchResult::default();
+    let calls = Rc::new(Cell::new(0));
+
+    let mut first = pin!(Text::load_async("wakers", fetch(&result, &calls)));
+    let mut second = pin!(Text::load_async("wakers", fetch(&result, &calls)));
+
+    let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+    let waker = Waker::from(wakes.clone());
+
+    assert!(poll(first.as_mut()).is_pending());
+    for _ in 0..3 {
+        assert!(second.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+    }
+
+    *result.borrow_mut() = Some(Ok(b"woken".to_vec()));
+
+    assert!(poll(first.as_mut()).is_ready());
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+    assert!(poll(second.as_mut()).is_ready());
+
+    Text::free_with_name("wakers");
+}
+
+fn ready<F: Future>(future: F) -> F::Output {
+    let Poll::Ready(output) = poll(pin!(future)) else {
+        panic!("future is not ready");
+    };
+    output
+}
+
+#[test]
+#[serial]
+fn download_with_memory_fetcher() -> Result<()> {
+    set_current_thread_as_main();
+
+    Text::set_fetcher(MemoryFetcher::new().with("https://fixtures/a.txt", "from memory"));
+
+    let text = ready(Text::download("memory", "https://fixtures/a.txt"))?;
+    assert_eq!(text.text, "from memory");
+
+    let err = ready(Text::download("missing", "https://fixtures/b.txt")).err().unwrap();
+    assert_eq!(err.to_string(), "No data for url: https://fixtures/b.txt");
+
+    Text::free_with_name("memory");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn download_with_file_fetcher() -> Result<()> {
+    set_current_thread_as_main();
+
+    let path = std::env::temp_dir().join("refs_file_fetcher.txt");
+    std::fs::write(&path, "from file")?;
+
+    Text::set_fetcher(FileFetcher);
+
+    let text = ready(Text::download("file", &format!("file://{}", path.display())))?;
+    assert_eq!(text.text, "from file");
+
+    assert!(ready(Text::download("no_file", "file:///refs/missing.txt")).is_err());
+
+    Text::free_with_name("file");
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[test]
+fn fetchers_read_when_polled() -> Result<()> {
+    let path = std::env::temp_dir().join("refs_lazy_fetch.txt");
+    let _ = std::fs::remove_file(&path);
+    let url = path.display().to_string();
+
+    let file = FileFetcher.fetch(&url);
+    std::fs::write(&path, "written later")?;
+    assert_eq!(ready(file)?, b"written later");
+
+    let memory = MemoryFetcher::new();
+    let fetch = memory.fetch("later");
+    memory.insert("later", "inserted later");
+    assert_eq!(ready(fetch)?, b"inserted later");
+
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+mod fallible {
+    use anyhow::Result;
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::manage::{DataManager, LoadError, MemoryFetcher};
+
+    number_fixture!(Number, "refs_numbers");
+
+    #[test]
+    #[serial]
+    fn try_get_and_try_load() -> Result<()> {
+        set_current_thread_as_main();
+
+        let root = root();
+        std::fs::write(root.join("five"), "5")?;
+
+        assert_eq!(Number::try_get("five")?.0, 5);
+
+        let err: LoadError = Number::try_get("missing").err().unwrap();
+        assert_eq!(err.name, "missing");
+        assert_eq!(err.path, Some(root.join("missing")));
+        assert!(err.to_string().starts_with(&format!(
+            "Failed to load missing from {}: ",
+            root.join("missing").display()
+        )));
+
+        let err = Number::try_load(b"abc", "abc").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Failed to load abc: invalid digit found in string"
+        );
+        assert!(err.path.is_none());
+        assert!(Number::get_existing("abc").is_none());
+
+        assert_eq!(Number::try_load(b"10", "ten")?.0, 10);
+
+        Number::set_fetcher(MemoryFetcher::new().with("bad", "not a number"));
+        let err = super::ready(Number::download("bad", "bad")).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Failed to load bad: invalid digit found in string"
+        );
+
+        std::fs::remove_file(root.join("five"))?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    #[should_panic(expected = "Failed to load nan: invalid digit found in string")]
+    fn load_panics_on_error() {
+        set_current_thread_as_main();
+        Number::load(b"nan", "nan");
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod background {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::{
+        main_lock::pump,
+        manage::{DataManager, LoadStatus, Placeholder},
+    };
+
+    number_fixture!(Model, "refs_models");
+
+    impl Placeholder for Model {
+        fn placeholder() -> Self {
+            Self(-1)
+        }
+    }
+
+    /// Pumps main thread queue until `name` stops loading.
+    fn wait_for(name: &str) -> LoadStatus {
+        let start = std::time::Instant::now();
+
+        loop {
+            pump();
+
+            let status = Model::load_status(name);
+            if !status.is_loading() {
+                return status;
+            }
+
+            assert!(start.elapsed() < Duration::from_secs(10), "{name} is not loaded");
+            std::thread::yield_now();
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn get_async_swaps_placeholder() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("async");
+        std::fs::write(&path, "42")?;
+
+        assert!(matches!(Model::load_status("async"), LoadStatus::NotLoaded));
+
+        let model = Model::get_async("async");
+
+        assert_eq!(model.0, -1);
+        assert!(Model::load_status("async").is_loading());
+        assert_eq!(Model::get_async("async").addr(), model.addr());
+
+        assert!(wait_for("async").is_loaded());
+        assert_eq!(model.0, 42);
+        assert!(Model::background_loads().is_empty());
+
+        Model::free_with_name("async");
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn get_async_failure_keeps_placeholder() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("async_bad");
+        std::fs::write(&path, "bad")?;
+
+        let model = Model::get_async("async_bad");
+
+        let LoadStatus::Failed(err) = wait_for("async_bad") else {
+            panic!("load didn't fail");
+        };
+        assert_eq!(err.to_string(), "invalid digit found in string");
+        assert_eq!(model.0, -1);
+
+        std::fs::write(&path, "3")?;
+        assert_eq!(Model::get_async("async_bad").addr(), model.addr());
+        assert!(wait_for("async_bad").is_loaded());
+        assert_eq!(model.0, 3);
+
+        Model::free_with_name("async_bad");
+        assert!(matches!(Model::load_status("async_bad"), LoadStatus::NotLoaded));
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn get_async_evicts_over_budget() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("async_big");
+        std::fs::write(&path, "200")?;
+        std::fs::write(root().join("async_small"), "100")?;
+
+        Model::eviction().set_max_bytes(Some(250), |model| {
+            usize::try_from(model.0).ok().filter(|bytes| *bytes >= 100).unwrap_or(0)
+        });
+
+        let small = Model::get("async_small");
+        let big = Model::get_async("async_big");
+
+        assert!(wait_for("async_big").is_loaded());
+        assert!(small.is_null());
+        assert_eq!(big.0, 200);
+        assert_eq!(Model::eviction().total_bytes(), 200);
+
+        Model::eviction().set_max_bytes(None, |_| 0);
+        Model::free_with_name("async_big");
+        std::fs::remove_file(path)?;
+        std::fs::remove_file(root().join("async_small"))?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn get_async_ignores_stale_load() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("async_stale");
+        std::fs::write(&path, "1")?;
+
+        Model::get_async("async_stale");
+        // Let the first load read the file.
+        std::thread::sleep(Duration::from_millis(100));
+
+        Model::free_with_name("async_stale");
+        std::fs::remove_file(&path)?;
+
+        let model = Model::get_async("async_stale");
+
+        assert!(matches!(wait_for("async_stale"), LoadStatus::Failed(_)));
+        std::thread::sleep(Duration::from_millis(100));
+        pump();
+
+        assert_eq!(model.0, -1);
+        assert!(matches!(Model::load_status("async_stale"), LoadStatus::Failed(_)));
+
+        Model::free_with_name("async_stale");
+
+        Ok(())
+    }
+}
+
+mod handle {
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    use anyhow::Result;
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::{main_lock::pump, manage::DataManager};
+
+    number_fixture!(Sprite, "refs_sprites");
+
+    #[test]
+    #[serial]
+    fn handles_release_resources() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("handled");
+        std::fs::write(&path, "7")?;
+
+        let first = Sprite::handle("handled");
+        let second = first.clone();
+        let weak = first.weak();
+
+        assert_eq!(first.0, 7);
+        assert_eq!(first.name(), "handled");
+        assert_eq!(Sprite::handles().count("handled"), 2);
+
+        drop(first);
+        assert!(!weak.is_null());
+
+        drop(second);
+        assert!(weak.is_null());
+        assert!(Sprite::get_existing("handled").is_none());
+        assert_eq!(Sprite::handles().count("handled"), 0);
+
+        assert!(Sprite::try_handle("no_handle").is_err());
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn handle_drop_callback_reads_storage() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("drop_callback");
+        std::fs::write(&path, "9")?;
+
+        let handle = Sprite::handle("drop_callback");
+        let stored = Rc::new(Cell::new(None));
+
+        let callback_stored = stored.clone();
+        handle
+            .weak()
+            .on_drop(move || {
+                callback_stored.set(Some(Sprite::get_existing("drop_callback").is_some()));
+            })
+            .detach();
+
+        drop(handle);
+        assert_eq!(stored.get(), Some(false));
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn handles_grace_period() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("grace");
+        std::fs::write(&path, "8")?;
+
+        Sprite::set_grace_period(Duration::from_secs(60));
+
+        let weak = Sprite::handle("grace").weak();
+        assert!(!weak.is_null());
+        assert!(Sprite::release_expired().is_empty());
+
+        let handle = Sprite::handle("grace");
+        assert_eq!(handle.weak().addr(), weak.addr());
+        drop(handle);
+
+        Sprite::set_grace_period(Duration::ZERO);
+        assert_eq!(Sprite::release_expired(), vec!["grace"]);
+        assert!(weak.is_null());
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn freed_name_stops_grace_period() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("refreed");
+        std::fs::write(&path, "5")?;
+
+        Sprite::set_grace_period(Duration::from_secs(60));
+        drop(Sprite::handle("refreed"));
+
+        Sprite::free_with_name("refreed");
+        let weak = Sprite::get("refreed");
+
+        Sprite::set_grace_period(Duration::ZERO);
+        assert!(Sprite::release_expired().is_empty());
+        assert!(!weak.is_null());
+
+        Sprite::free_with_name("refreed");
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn handle_dropped_on_worker_thread() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("worker");
+        std::fs::write(&path, "6")?;
+
+        let handle = Sprite::handle("worker");
+        let weak = handle.weak();
+
+        std::thread::spawn(move || drop(handle)).join().unwrap();
+
+        assert!(Sprite::get_existing("worker").is_none());
+        assert_eq!(Sprite::handles().count("worker"), 0);
+        assert!(!weak.is_null());
+
+        assert_eq!(pump(), 1);
+        assert!(weak.is_null());
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+}
+
+mod hot_reload {
+    use std::{
+        path::Path,
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
+
+    use anyhow::Result;
+    use hreads::set_current_thread_as_main;
+    use parking_lot::Mutex;
+    use serial_test::serial;
+
+    use crate::manage::DataManager;
+
+    number_fixture!(Setting, "refs_settings");
+
+    /// Writes file with modification time in the future
+    /// so it differs from previous write.
+    fn write(path: &Path, contents: &str, seconds: u64) -> Result<()> {
+        std::fs::write(path, contents)?;
+        let file = std::fs::File::options().write(true).open(path)?;
+        file.set_modified(SystemTime::now() + Duration::from_secs(seconds))?;
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn hot_reload() -> Result<()> {
+        set_current_thread_as_main();
+
+        let path = root().join("reload");
+        write(&path, "1", 0)?;
+
+        let setting = Setting::get("reload");
+        Setting::watch(Duration::ZERO);
+
+        let reloads = Arc::new(Mutex::new(vec![]));
+        let log = reloads.clone();
+        Setting::on_reload(move |name, setting| log.lock().push(format!("{name}={}", setting.0)));
+
+        assert!(Setting::poll_reload().is_empty());
+
+        write(&path, "2", 10)?;
+        assert_eq!(Setting::poll_reload(), vec!["reload"]);
+        assert_eq!(setting.0, 2);
+        assert_eq!(*reloads.lock(), vec!["reload=2"]);
+
+        write(&path, "not a number", 20)?;
+        assert!(Setting::reload_changed().is_empty());
+        assert_eq!(setting.0, 2);
+
+        Setting::unwatch();
+        write(&path, "3", 30)?;
+        assert!(Setting::poll_reload().is_empty());
+        assert_eq!(setting.0, 2);
+
+        Setting::watch(Duration::from_secs(60));
+        write(&path, "4", 40)?;
+        assert_eq!(Setting::poll_reload(), vec!["reload"]);
+        assert_eq!(setting.0, 4);
+
+        write(&path, "5", 50)?;
+        assert!(Setting::poll_reload().is_empty());
+        assert_eq!(setting.0, 4);
+        assert_eq!(Setting::reload_changed(), vec!["reload"]);
+        assert_eq!(setting.0, 5);
+
+        Setting::unwatch();
+        Setting::free_with_name("reload");
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn hot_reload_evicts_over_budget() -> Result<()> {
+        set_current_thread_as_main();
+
+        let a_path = root().join("budget_a");
+        let b_path = root().join("budget_b");
+        write(&a_path, "100", 0)?;
+        write(&b_path, "100", 0)?;
+
+        Setting::eviction().set_max_bytes(Some(250), |setting| usize::try_from(setting.0).unwrap_or(0));
+
+        let a = Setting::get("budget_a");
+        let b = Setting::get("budget_b");
+        assert_eq!(Setting::eviction().total_bytes(), 200);
+
+        Setting::watch(Duration::ZERO);
+        write(&b_path, "200", 10)?;
+
+        assert_eq!(Setting::reload_changed(), vec!["budget_b"]);
+        assert!(a.is_null());
+        assert_eq!(b.0, 200);
+        assert_eq!(Setting::eviction().total_bytes(), 200);
+
+        Setting::unwatch();
+        Setting::eviction().set_max_bytes(None, |_| 0);
+        Setting::free_with_name("budget_b");
+        std::fs::remove_file(a_path)?;
+        std::fs::remove_file(b_path)?;
+
+        Ok(())
+    }
+}
+
+mod eviction {
+    use std::path::Path;
+
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::{
+        main_lock::pump,
+        manage::{DataManager, EvictionPolicy, TryResourceLoader},
+    };
+
+    struct Blob(Vec<u8>);
+
+    impl TryResourceLoader for Blob {
+        type Error = std::io::Error;
+
+        fn try_load_path(path: &Path) -> Result<Self, Self::Error> {
+            Ok(Self(std::fs::read(path)?))
+        }
+
+        fn try_load_data(data: &[u8], _name: &str) -> Result<Self, Self::Error> {
+            Ok(Self(data.to_vec()))
+        }
+    }
+
+    crate::managed!(refs, Blob);
+
+    fn stored() -> Vec<String> {
+        Blob::storage().keys().cloned().collect()
+    }
+
+    fn reset() {
+        Blob::eviction().set_max_entries(None).set_max_bytes(None, |blob| blob.0.len());
+        for name in stored() {
+            Blob::free_with_name(name);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn lru_entry_budget() {
+        set_current_thread_as_main();
+
+        Blob::eviction().set_policy(EvictionPolicy::Lru).set_max_entries(Some(2));
+
+        let a = Blob::load(b"a", "a");
+        Blob::load(b"b", "b");
+
+        assert!(Blob::get_existing("a").is_some());
+
+        Blob::load(b"c", "c");
+
+        assert_eq!(stored(), vec!["a", "c"]);
+        assert!(!a.is_null());
+        assert!(Blob::get_existing("b").is_none());
+
+        Blob::eviction().set_max_entries(Some(1));
+        assert_eq!(Blob::evict(), vec!["a"]);
+        assert!(a.is_null());
+
+        reset();
+    }
+
+    #[test]
+    #[serial]
+    fn lfu_entry_budget() {
+        set_current_thread_as_main();
+
+        Blob::eviction().set_policy(EvictionPolicy::Lfu).set_max_entries(Some(2));
+
+        Blob::load(b"a", "a");
+        let b = Blob::load(b"b", "b");
+
+        Blob::get_existing("a");
+        Blob::get_existing("a");
+        Blob::get_existing("b");
+
+        Blob::load(b"c", "c");
+
+        assert_eq!(stored(), vec!["a", "c"]);
+        assert!(b.is_null());
+
+        reset();
+    }
+
+    #[test]
+    #[serial]
+    fn byte_budget_and_pinning() {
+        set_current_thread_as_main();
+
+        Blob::eviction()
+            .set_policy(EvictionPolicy::Lru)
+            .set_max_bytes(Some(10), |blob| blob.0.len());
+
+        Blob::pin("pinned");
+        Blob::load(b"123456", "pinned");
+        let small = Blob::load(b"12", "small");
+
+        assert_eq!(Blob::eviction().total_bytes(), 8);
+
+        let big = Blob::load(b"1234", "big");
+
+        assert!(small.is_null());
+        assert_eq!(stored(), vec!["big", "pinned"]);
+        assert_eq!(Blob::eviction().total_bytes(), 10);
+
+        Blob::load(b"12345", "bigger");
+
+        assert!(big.is_null());
+        assert_eq!(stored(), vec!["bigger", "pinned"]);
+        assert_eq!(Blob::eviction().total_bytes(), 11);
+
+        Blob::unpin("pinned");
+
+        assert_eq!(stored(), vec!["bigger"]);
+        assert_eq!(Blob::eviction().total_bytes(), 5);
+
+        reset();
+    }
+
+    #[test]
+    #[serial]
+    fn evict_on_worker_thread() {
+        set_current_thread_as_main();
+
+        Blob::eviction().set_policy(EvictionPolicy::Lru).set_max_entries(Some(1));
+
+        let a = Blob::load(b"a", "a");
+
+        std::thread::spawn(|| {
+            super::ready(Blob::load_async("b", async { Ok(b"b".to_vec()) })).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(stored(), vec!["b"]);
+        assert!(!a.is_null());
+
+        assert_eq!(pump(), 1);
+        assert!(a.is_null());
+
+        reset();
+    }
+}
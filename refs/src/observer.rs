@@ -0,0 +1,128 @@
+use std::{
+    panic::Location,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
+
+use parking_lot::RwLock;
+
+use crate::RawPointer;
+
+/// Receives every allocation and deallocation registered by reference
+/// counter. Can be used for profilers and leak trackers.
+///
+/// Callbacks can be called from any thread. `on_alloc` is called from the
+/// thread that created the `Own`, `on_free` always from main thread.
+pub trait RefsObserver: Send + Sync {
+    fn on_alloc(&self, ptr: RawPointer, location: &'static Location<'static>);
+    fn on_free(&self, ptr: RawPointer);
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ObserverId(u64);
+
+static OBSERVERS: RwLock<Vec<(ObserverId, Arc<dyn RefsObserver>)>> = RwLock::new(Vec::new());
+static HAS_OBSERVERS: AtomicBool = AtomicBool::new(false);
+
+pub fn add_observer(observer: impl RefsObserver + 'static) -> ObserverId {
+    static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
+    let id = ObserverId(LAST_ID.fetch_add(1, Ordering::Relaxed) + 1);
+
+    let mut observers = OBSERVERS.write();
+    observers.push((id, Arc::new(observer)));
+    HAS_OBSERVERS.store(true, Ordering::Release);
+
+    id
+}
+
+/// Returns `false` if observer with this id was not registered.
+pub fn remove_observer(id: ObserverId) -> bool {
+    let mut observers = OBSERVERS.write();
+    let len = observers.len();
+    observers.retain(|(observer_id, _)| *observer_id != id);
+    HAS_OBSERVERS.store(!observers.is_empty(), Ordering::Release);
+    observers.len() != len
+}
+
+fn observers() -> Vec<Arc<dyn RefsObserver>> {
+    OBSERVERS.read().iter().map(|(_, observer)| observer.clone()).collect()
+}
+
+pub(crate) fn notify_alloc(ptr: RawPointer, location: &'static Location<'static>) {
+    if !HAS_OBSERVERS.load(Ordering::Acquire) {
+        return;
+    }
+
+    for observer in observers() {
+        observer.on_alloc(ptr, location);
+    }
+}
+
+pub(crate) fn notify_free(ptr: RawPointer) {
+    if !HAS_OBSERVERS.load(Ordering::Acquire) {
+        return;
+    }
+
+    for observer in observers() {
+        observer.on_free(ptr);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, panic::Location, sync::Arc};
+
+    use hreads::set_current_thread_as_main;
+    use parking_lot::Mutex;
+    use serial_test::serial;
+
+    use crate::{Own, RawPointer, RefsObserver, add_observer, remove_observer};
+
+    #[derive(Default, Clone)]
+    struct LeakTracker {
+        live: Arc<Mutex<BTreeMap<usize, (&'static str, u32)>>>,
+    }
+
+    impl RefsObserver for LeakTracker {
+        fn on_alloc(&self, ptr: RawPointer, location: &'static Location<'static>) {
+            self.live.lock().insert(ptr.addr(), (ptr.type_name(), location.line()));
+        }
+
+        fn on_free(&self, ptr: RawPointer) {
+            self.live.lock().remove(&ptr.addr());
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn observer() {
+        set_current_thread_as_main();
+
+        let tracker = LeakTracker::default();
+        let id = add_observer(tracker.clone());
+
+        let line = line!() + 1;
+        let a = Own::new(5_u8);
+        let b = Own::new(String::from("b"));
+
+        assert_eq!(tracker.live.lock().len(), 2);
+        assert_eq!(tracker.live.lock().get(&a.raw().addr()), Some(&("u8", line)));
+
+        drop(a);
+
+        assert_eq!(
+            tracker.live.lock().values().copied().collect::<Vec<_>>(),
+            vec![("alloc::string::String", line + 1)]
+        );
+
+        assert!(remove_observer(id));
+        assert!(!remove_observer(id));
+
+        drop(b);
+
+        assert_eq!(tracker.live.lock().len(), 1);
+    }
+}
@@ -0,0 +1,227 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{Addr, Own, RawPointer, Stamp, Weak, main_lock::MainLock};
+
+type Key = (Addr, Stamp);
+type Callback = Box<dyn FnOnce()>;
+type Hook = Rc<dyn Fn(RawPointer)>;
+
+#[derive(Default)]
+struct DropCallbacks {
+    last_id: u64,
+    objects: HashMap<Key, Vec<(u64, Callback)>>,
+    global:  Vec<(u64, Hook)>,
+}
+
+impl DropCallbacks {
+    fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        self.last_id
+    }
+}
+
+static CALLBACKS: MainLock<DropCallbacks> = MainLock::new();
+
+/// Keeps drop callback registered. Dropping it cancels the callback.
+#[must_use = "Dropping subscription cancels the callback"]
+pub struct DropSubscription {
+    id:  u64,
+    key: Option<Key>,
+}
+
+impl DropSubscription {
+    /// Keeps callback registered for the whole object lifetime.
+    pub fn detach(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for DropSubscription {
+    fn drop(&mut self) {
+        let mut callbacks = CALLBACKS.get_mut();
+
+        let Some(key) = self.key else {
+            let _cancelled = callbacks
+                .global
+                .iter()
+                .position(|(id, _)| *id == self.id)
+                .map(|index| callbacks.global.remove(index));
+            drop(callbacks);
+            return;
+        };
+
+        let Some(object) = callbacks.objects.get_mut(&key) else {
+            return;
+        };
+
+        // Cancelled callback is dropped after the lock is released
+        // because it may own objects with their own drop callbacks.
+        let _cancelled = object
+            .iter()
+            .position(|(id, _)| *id == self.id)
+            .map(|index| object.remove(index));
+
+        if object.is_empty() {
+            callbacks.objects.remove(&key);
+        }
+
+        drop(callbacks);
+    }
+}
+
+impl<T: ?Sized> Weak<T> {
+    /// Calls `callback` on main thread right before the object is dropped.
+    /// Does nothing if the object is already freed.
+    pub fn on_drop(&self, callback: impl FnOnce() + 'static) -> DropSubscription {
+        let mut callbacks = CALLBACKS.get_mut();
+        let id = callbacks.next_id();
+
+        if self.is_null() {
+            return DropSubscription { id, key: None };
+        }
+
+        let key = self.identity();
+        callbacks.objects.entry(key).or_default().push((id, Box::new(callback)));
+
+        DropSubscription { id, key: Some(key) }
+    }
+}
+
+impl<T: ?Sized, A: std::alloc::Allocator> Own<T, A> {
+    /// Calls `callback` on main thread right before the object is dropped.
+    pub fn on_drop(&self, callback: impl FnOnce() + 'static) -> DropSubscription {
+        self.weak().on_drop(callback)
+    }
+}
+
+/// Calls `hook` on main thread before any `Own` is dropped.
+pub fn on_any_drop(hook: impl Fn(RawPointer) + 'static) -> DropSubscription {
+    let mut callbacks = CALLBACKS.get_mut();
+    let id = callbacks.next_id();
+    callbacks.global.push((id, Rc::new(hook)));
+    DropSubscription { id, key: None }
+}
+
+pub(crate) fn notify_drop(raw: RawPointer) {
+    let mut callbacks = CALLBACKS.get_mut();
+
+    if callbacks.objects.is_empty() && callbacks.global.is_empty() {
+        return;
+    }
+
+    let global = callbacks.global.iter().map(|(_, hook)| hook.clone()).collect::<Vec<_>>();
+    let object = callbacks.objects.remove(&(raw.addr(), raw.stamp())).unwrap_or_default();
+    drop(callbacks);
+
+    for hook in global {
+        hook(raw);
+    }
+
+    for (_, callback) in object {
+        callback();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::{Own, Weak, on_any_drop};
+
+    #[test]
+    #[serial]
+    fn on_drop_callback() {
+        set_current_thread_as_main();
+
+        let log = Rc::new(RefCell::new(vec![]));
+
+        let own = Own::new(5);
+        let weak = own.weak();
+
+        let l = log.clone();
+        let _sub = weak.on_drop(move || {
+            assert!(weak.is_ok());
+            l.borrow_mut().push(*weak);
+        });
+
+        let l = log.clone();
+        own.on_drop(move || l.borrow_mut().push(10)).detach();
+
+        assert!(log.borrow().is_empty());
+        drop(own);
+        assert_eq!(*log.borrow(), vec![5, 10]);
+    }
+
+    #[test]
+    #[serial]
+    fn on_drop_cancel() {
+        set_current_thread_as_main();
+
+        let log = Rc::new(RefCell::new(vec![]));
+
+        let own = Own::new(5);
+
+        let l = log.clone();
+        let sub = own.on_drop(move || l.borrow_mut().push(1));
+        drop(sub);
+
+        let l = log.clone();
+        let _null = Weak::<i32>::default().on_drop(move || l.borrow_mut().push(2));
+
+        drop(own);
+        assert!(log.borrow().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn on_any_drop_hook() {
+        set_current_thread_as_main();
+
+        let log = Rc::new(RefCell::new(vec![]));
+
+        let l = log.clone();
+        let sub = on_any_drop(move |raw| l.borrow_mut().push(raw.type_name()));
+
+        drop(Own::new(5_u8));
+        drop(Own::new(String::new()));
+
+        drop(sub);
+
+        drop(Own::new(5_u32));
+
+        assert_eq!(*log.borrow(), vec!["u8", "alloc::string::String"]);
+    }
+
+    #[test]
+    #[serial]
+    fn on_drop_reentrant() {
+        set_current_thread_as_main();
+
+        let log = Rc::new(RefCell::new(vec![]));
+
+        let parent = Own::new(1);
+        let child = Own::new(2);
+
+        let l = log.clone();
+        child
+            .on_drop(move || {
+                l.borrow_mut().push(2);
+            })
+            .detach();
+
+        let child = RefCell::new(Some(child));
+        let l = log.clone();
+        parent
+            .on_drop(move || {
+                l.borrow_mut().push(1);
+                child.borrow_mut().take();
+            })
+            .detach();
+
+        drop(parent);
+        assert_eq!(*log.borrow(), vec![1, 2]);
+    }
+}
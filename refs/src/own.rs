@@ -1,9 +1,12 @@
 use std::{
+    alloc::{Allocator, Global, Layout},
     any::type_name,
     fmt::{Debug, Formatter},
-    marker::Unsize,
+    marker::{PhantomData, Unsize},
+    mem::MaybeUninit,
     ops::{CoerceUnsized, Deref, DerefMut},
-    ptr::from_ref,
+    panic::Location,
+    ptr::NonNull,
 };
 
 use hreads::is_main_thread;
@@ -13,41 +16,110 @@ use crate::{AsAny, PTR_SIZE, RawPointer, Weak, ref_counter::RefCounter};
 pub(crate) type Stamp = u64;
 pub(crate) type Addr = usize;
 
-pub struct Own<T: ?Sized> {
-    bx:        Box<T>,
+/// Owning pointer. Only `Weak` pointers can be created from it.
+///
+/// The value is stored in memory of allocator `A`. Weak pointers don't
+/// depend on the allocator.
+pub struct Own<T: ?Sized, A: Allocator = Global> {
+    ptr:       NonNull<T>,
     stamp:     Stamp,
     type_name: &'static str,
+    alloc:     A,
+    _owns:     PhantomData<T>,
 }
 
-unsafe impl<T: ?Sized> Send for Own<T> {}
-unsafe impl<T: ?Sized> Sync for Own<T> {}
+unsafe impl<T: ?Sized, A: Allocator + Send> Send for Own<T, A> {}
+unsafe impl<T: ?Sized, A: Allocator + Sync> Sync for Own<T, A> {}
 
 impl<T: Sized + 'static> Own<T> {
-    #[cfg_attr(feature = "pointers_info", track_caller)]
+    #[track_caller]
     pub fn new(val: T) -> Self {
-        let type_name = std::any::type_name::<T>();
+        Self::new_in(val, Global)
+    }
+
+    /// Constructs object which needs a `Weak` to itself during
+    /// construction. The `Weak` becomes valid after `make` returns.
+    /// Until then `get` returns `None` and deref panics.
+    #[track_caller]
+    pub fn new_cyclic(make: impl FnOnce(Weak<T>) -> T) -> Self {
+        struct Reservation<T> {
+            slot: *mut MaybeUninit<T>,
+        }
+
+        impl<T> Drop for Reservation<T> {
+            fn drop(&mut self) {
+                RefCounter::cancel(self.slot as Addr);
+                drop(unsafe { Box::from_raw(self.slot) });
+            }
+        }
+
+        let slot = Box::into_raw(Box::<T>::new_uninit());
+        // Exposed for `weak_from_ptr`.
+        let address = slot.expose_provenance();
+
+        assert_ne!(
+            address, 1,
+            "Invalid address. In could be a closure or empty type."
+        );
+
+        let reservation = Reservation { slot };
+
+        let weak = Weak {
+            ptr:       slot.cast::<T>(),
+            stamp:     RefCounter::reserve(address),
+            type_name: std::any::type_name::<T>(),
+        };
+
+        let val = make(weak);
+
+        std::mem::forget(reservation);
+
+        unsafe { slot.write(MaybeUninit::new(val)) };
+
+        let stamp = RefCounter::commit(address, type_name::<T>(), Location::caller());
+
+        Self {
+            ptr: unsafe { NonNull::new_unchecked(slot.cast()) },
+            stamp,
+            type_name: std::any::type_name::<T>(),
+            alloc: Global,
+            _owns: PhantomData,
+        }
+    }
+}
 
+impl<T: Sized + 'static, A: Allocator> Own<T, A> {
+    #[track_caller]
+    pub fn new_in(val: T, alloc: A) -> Self {
         // #[cfg(feature = "stats")]
         // crate::stats::adjust_stat(type_name, 1);
 
-        let val = Box::new(val);
-        let address = from_ref::<T>(&val).cast::<u8>() as usize;
+        let (ptr, alloc) = Box::into_raw_with_allocator(Box::new_in(val, alloc));
+
+        unsafe { Self::from_allocation(NonNull::new_unchecked(ptr), alloc) }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to initialized value allocated by `alloc`.
+    #[track_caller]
+    pub(crate) unsafe fn from_allocation(ptr: NonNull<T>, alloc: A) -> Self {
+        // Exposed for `weak_from_ptr`.
+        let address = ptr.as_ptr().expose_provenance();
 
         assert_ne!(
             address, 1,
             "Invalid address. In could be a closure or empty type."
         );
 
-        #[cfg(feature = "pointers_info")]
-        let stamp = RefCounter::add(address, std::panic::Location::caller());
-
-        #[cfg(not(feature = "pointers_info"))]
-        let stamp = RefCounter::add(address);
+        let stamp = RefCounter::add(address, type_name::<T>(), Location::caller());
 
         Self {
-            bx: val,
+            ptr,
             stamp,
-            type_name,
+            type_name: std::any::type_name::<T>(),
+            alloc,
+            _owns: PhantomData,
         }
     }
 }
@@ -55,7 +127,7 @@ impl<T: Sized + 'static> Own<T> {
 impl<T: ?Sized + AsAny> Own<T> {
     pub fn downcast<U: 'static>(self) -> Own<U> {
         let (bx, stamp) = unsafe {
-            let bx = std::ptr::read(std::ptr::addr_of!(self.bx));
+            let bx = Box::from_raw(self.ptr.as_ptr());
             let stamp = self.stamp;
             std::mem::forget(self);
             (bx, stamp)
@@ -71,9 +143,11 @@ impl<T: ?Sized + AsAny> Own<T> {
         });
 
         Own {
-            bx,
+            ptr: NonNull::from(Box::leak(bx)),
             stamp,
             type_name: std::any::type_name::<U>(),
+            alloc: Global,
+            _owns: PhantomData,
         }
     }
 
@@ -82,7 +156,7 @@ impl<T: ?Sized + AsAny> Own<T> {
     }
 }
 
-impl<T: ?Sized> Own<T> {
+impl<T: ?Sized, A: Allocator> Own<T, A> {
     #[cfg(feature = "checks")]
     fn check() {
         assert!(
@@ -94,13 +168,17 @@ impl<T: ?Sized> Own<T> {
     }
 }
 
-impl<T: ?Sized> Own<T> {
+impl<T: ?Sized, A: Allocator> Own<T, A> {
     pub(crate) fn addr(&self) -> usize {
-        from_ref::<T>(self.bx.as_ref()).cast::<u8>() as usize
+        self.ptr.as_ptr().cast::<u8>() as usize
+    }
+
+    pub(crate) fn identity(&self) -> (Addr, Stamp) {
+        (self.addr(), self.stamp)
     }
 }
 
-impl<T: ?Sized> Drop for Own<T> {
+impl<T: ?Sized, A: Allocator> Drop for Own<T, A> {
     #[track_caller]
     fn drop(&mut self) {
         if !is_main_thread() {
@@ -108,31 +186,45 @@ impl<T: ?Sized> Drop for Own<T> {
             panic!("Dropping Own<{}> on non main thread", type_name::<T>());
         }
 
+        crate::on_drop::notify_drop(self.raw());
+
         // #[cfg(feature = "stats")]
         // crate::stats::adjust_stat(self.type_name, -1);
-        #[cfg(feature = "pointers_info")]
-        RefCounter::remove(self.addr(), std::backtrace::Backtrace::capture());
-        #[cfg(not(feature = "pointers_info"))]
-        RefCounter::remove(self.addr());
+        RefCounter::remove(
+            self.addr(),
+            self.type_name,
+            #[cfg(feature = "pointers_info")]
+            std::backtrace::Backtrace::capture(),
+        );
+
+        let ptr = self.ptr.as_ptr();
+
+        unsafe {
+            let layout = Layout::for_value_raw(ptr);
+            ptr.drop_in_place();
+            if layout.size() != 0 {
+                self.alloc.deallocate(self.ptr.cast(), layout);
+            }
+        }
     }
 }
 
-impl<T: ?Sized> Deref for Own<T> {
+impl<T: ?Sized, A: Allocator> Deref for Own<T, A> {
     type Target = T;
     fn deref(&self) -> &T {
-        self.bx.deref()
+        unsafe { self.ptr.as_ref() }
     }
 }
 
-impl<T: ?Sized> DerefMut for Own<T> {
+impl<T: ?Sized, A: Allocator> DerefMut for Own<T, A> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         #[cfg(feature = "checks")]
         Self::check();
-        self.bx.deref_mut()
+        unsafe { self.ptr.as_mut() }
     }
 }
 
-impl<T: ?Sized> Own<T> {
+impl<T: ?Sized, A: Allocator> Own<T, A> {
     pub fn weak(&self) -> Weak<T> {
         Weak {
             ptr:       self.ptr(),
@@ -154,7 +246,7 @@ impl<T: ?Sized> Own<T> {
     }
 
     pub fn ptr(&self) -> *mut T {
-        (&raw const *self.bx).cast_mut()
+        self.ptr.as_ptr()
     }
 
     pub fn raw(&self) -> RawPointer {
@@ -168,42 +260,46 @@ impl<T: Default + Sized + 'static> Default for Own<T> {
     }
 }
 
-impl<T: ?Sized + Debug> Debug for Own<T> {
+impl<T: ?Sized + Debug, A: Allocator> Debug for Own<T, A> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         self.deref().fmt(f)
     }
 }
 
-impl<T: ?Sized + PartialEq> PartialEq for Own<T> {
+impl<T: ?Sized + PartialEq, A: Allocator> PartialEq for Own<T, A> {
     fn eq(&self, other: &Self) -> bool {
         self.deref().eq(other.deref())
     }
 }
 
-impl<T: ?Sized + PartialEq> PartialEq<T> for Own<T> {
+impl<T: ?Sized + PartialEq, A: Allocator> PartialEq<T> for Own<T, A> {
     fn eq(&self, other: &T) -> bool {
         self.deref().eq(other)
     }
 }
 
-impl<T, U> CoerceUnsized<Own<U>> for Own<T>
+impl<T, U, A> CoerceUnsized<Own<U, A>> for Own<T, A>
 where
     T: Unsize<U> + ?Sized,
     U: ?Sized,
+    A: Allocator,
 {
 }
 
 #[cfg(test)]
 mod tests {
     use std::{
+        alloc::{AllocError, Allocator, Global, Layout},
+        cell::Cell,
         ops::{Deref, DerefMut},
-        sync::atomic::{AtomicU64, Ordering},
+        ptr::NonNull,
+        sync::atomic::{AtomicU64, AtomicUsize, Ordering},
     };
 
     use hreads::set_current_thread_as_main;
     use serial_test::serial;
 
-    use crate::Own;
+    use crate::{Own, Weak};
 
     #[test]
     #[serial]
@@ -288,4 +384,115 @@ mod tests {
         assert_eq!("5", &format!("{five:?}"));
         assert_eq!(five, five_int);
     }
+
+    static LIVE_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone, Copy)]
+    struct CountingAlloc;
+
+    unsafe impl Allocator for CountingAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            LIVE_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            LIVE_ALLOCATIONS.fetch_sub(1, Ordering::Relaxed);
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn custom_allocator() {
+        set_current_thread_as_main();
+
+        trait Trait {
+            fn val(&self) -> i32;
+        }
+
+        impl Trait for i32 {
+            fn val(&self) -> i32 {
+                *self
+            }
+        }
+
+        let mut num = Own::new_in(5, CountingAlloc);
+        let unsized_num: Own<dyn Trait, CountingAlloc> = Own::new_in(10, CountingAlloc);
+        assert_eq!(LIVE_ALLOCATIONS.load(Ordering::Relaxed), 2);
+
+        *num = 7;
+        let weak = num.weak();
+        let unsized_weak = unsized_num.weak();
+
+        assert_eq!(weak.deref(), &7);
+        assert_eq!(unsized_weak.val(), 10);
+        assert_eq!(num, 7);
+
+        drop(num);
+        drop(unsized_num);
+
+        assert!(weak.is_null());
+        assert!(unsized_weak.is_null());
+        assert_eq!(LIVE_ALLOCATIONS.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    #[serial]
+    fn new_cyclic() {
+        set_current_thread_as_main();
+
+        struct Node {
+            this:  Weak<Node>,
+            value: i32,
+        }
+
+        let node = Own::new_cyclic(|this: Weak<Node>| {
+            assert!(this.was_initialized());
+            assert!(this.is_null());
+            assert!(this.get().is_none());
+            Node { this, value: 5 }
+        });
+
+        assert!(node.this.is_ok());
+        assert_eq!(node.this.value, 5);
+        assert!(node.this == node.weak());
+
+        let this = node.this;
+        drop(node);
+        assert!(this.is_null());
+    }
+
+    #[test]
+    #[serial]
+    #[should_panic(expected = "Defererencing weak pointer during Own::new_cyclic construction: i32")]
+    fn new_cyclic_deref_during_construction() {
+        set_current_thread_as_main();
+        let _ = Own::new_cyclic(|this: Weak<i32>| *this.deref());
+    }
+
+    #[test]
+    #[serial]
+    fn new_cyclic_panic_in_constructor() {
+        set_current_thread_as_main();
+
+        let leaked = Cell::new(Weak::default());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            Own::new_cyclic(|this: Weak<i32>| {
+                leaked.set(this);
+                panic!("constructor failed");
+            })
+        }));
+
+        assert!(result.is_err());
+
+        let leaked = leaked.get();
+        assert!(leaked.was_initialized());
+        assert!(leaked.is_null());
+        assert!(!crate::ref_counter::RefCounter::is_constructing(
+            leaked.addr(),
+            leaked.stamp
+        ));
+    }
 }
@@ -0,0 +1,251 @@
+use std::{
+    alloc::{AllocError, Allocator, Global, Layout},
+    cell::{Cell, UnsafeCell},
+    marker::PhantomData,
+    ptr::NonNull,
+};
+
+use hreads::assert_main_thread;
+
+use crate::Own;
+
+/// Free list shared by the pool and its `Own`s. Used only on main thread.
+///
+/// Freed when the pool is dropped and no slot is in use.
+struct Slots<T> {
+    free:       UnsafeCell<Vec<NonNull<u8>>>,
+    used:       Cell<usize>,
+    pool_alive: Cell<bool>,
+    _t:         PhantomData<fn() -> T>,
+}
+
+impl<T> Slots<T> {
+    #[allow(clippy::mut_from_ref)]
+    fn free(&self) -> &mut Vec<NonNull<u8>> {
+        unsafe { &mut *self.free.get() }
+    }
+
+    /// # Safety
+    ///
+    /// `slots` must not be used after this call unless the pool is alive
+    /// or some slot is in use.
+    unsafe fn release_if_unused(slots: NonNull<Self>) {
+        let this = unsafe { slots.as_ref() };
+
+        if this.pool_alive.get() || this.used.get() > 0 {
+            return;
+        }
+
+        for slot in this.free().drain(..) {
+            unsafe { Global.deallocate(slot, Layout::new::<T>()) };
+        }
+        drop(unsafe { Box::from_raw(slots.as_ptr()) });
+    }
+}
+
+/// Allocator of `Own`s created by `OwnPool`.
+///
+/// Allocations with layout of `T` reuse free slots of the pool and are
+/// returned to it on deallocation. Other layouts go to `Global`.
+pub struct PoolAlloc<T> {
+    slots: NonNull<Slots<T>>,
+}
+
+unsafe impl<T> Allocator for PoolAlloc<T> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout != Layout::new::<T>() {
+            return Global.allocate(layout);
+        }
+
+        let slots = unsafe { self.slots.as_ref() };
+
+        let slot = match slots.free().pop() {
+            Some(slot) => NonNull::slice_from_raw_parts(slot, layout.size()),
+            None => Global.allocate(layout)?,
+        };
+
+        slots.used.set(slots.used.get() + 1);
+        Ok(slot)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout != Layout::new::<T>() {
+            unsafe { Global.deallocate(ptr, layout) };
+            return;
+        }
+
+        let slots = unsafe { self.slots.as_ref() };
+        slots.used.set(slots.used.get() - 1);
+        slots.free().push(ptr);
+
+        unsafe { Slots::release_if_unused(self.slots) };
+    }
+}
+
+/// Typed pool of memory slots for `Own`.
+///
+/// Dropped `Own`s created by the pool return their slot for reuse instead
+/// of freeing it. Weak pointers to a recycled slot become null because the
+/// new `Own` gets a different stamp.
+///
+/// The pool and its `Own`s can be used only on main thread.
+pub struct OwnPool<T> {
+    slots: NonNull<Slots<T>>,
+}
+
+impl<T: 'static> OwnPool<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        // The pool is not `Send` so it stays on main thread.
+        assert_main_thread();
+
+        let free = (0..capacity).map(|_| Self::alloc_slot()).collect();
+        let slots = Box::new(Slots {
+            free:       UnsafeCell::new(free),
+            used:       Cell::new(0),
+            pool_alive: Cell::new(true),
+            _t:         PhantomData,
+        });
+
+        Self {
+            slots: NonNull::from(Box::leak(slots)),
+        }
+    }
+
+    #[track_caller]
+    pub fn own(&self, val: T) -> Own<T, PoolAlloc<T>> {
+        Own::new_in(val, PoolAlloc { slots: self.slots })
+    }
+
+    /// Number of allocated slots not used by any `Own`.
+    pub fn free_slots(&self) -> usize {
+        unsafe { self.slots.as_ref() }.free().len()
+    }
+
+    fn alloc_slot() -> NonNull<u8> {
+        Global
+            .allocate(Layout::new::<T>())
+            .unwrap_or_else(|_| std::alloc::handle_alloc_error(Layout::new::<T>()))
+            .cast()
+    }
+}
+
+impl<T: 'static> Default for OwnPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for OwnPool<T> {
+    fn drop(&mut self) {
+        unsafe { self.slots.as_ref() }.pool_alive.set(false);
+        unsafe { Slots::release_if_unused(self.slots) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        ops::Deref,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::{Own, OwnPool};
+
+    #[test]
+    #[serial]
+    fn pool_reuses_slots() {
+        set_current_thread_as_main();
+
+        let pool = OwnPool::with_capacity(2);
+        assert_eq!(pool.free_slots(), 2);
+
+        let a = pool.own(5);
+        let b = pool.own(10);
+        assert_eq!(pool.free_slots(), 0);
+        assert_eq!(*a + *b, 15);
+
+        let a_weak = a.weak();
+        let a_addr = a.addr();
+        drop(a);
+
+        assert_eq!(pool.free_slots(), 1);
+        assert!(a_weak.is_null());
+
+        let c = pool.own(20);
+        assert_eq!(c.addr(), a_addr);
+        assert!(a_weak.is_null());
+        assert!(c.weak().is_ok());
+        assert_eq!(c.weak().deref(), &20);
+
+        let d = pool.own(30);
+        assert_eq!(pool.free_slots(), 0);
+
+        drop(b);
+        drop(c);
+        drop(d);
+        assert_eq!(pool.free_slots(), 3);
+    }
+
+    #[test]
+    #[serial]
+    fn pool_drops_values() {
+        set_current_thread_as_main();
+
+        static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+        struct ToDrop {
+            _a: u32,
+        }
+
+        impl Drop for ToDrop {
+            fn drop(&mut self) {
+                DROPPED.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let pool = OwnPool::new();
+
+        let a = pool.own(ToDrop { _a: 1 });
+        let b = pool.own(ToDrop { _a: 2 });
+
+        drop(pool);
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 0);
+
+        drop(a);
+        drop(b);
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    #[serial]
+    fn pool_unsized() {
+        set_current_thread_as_main();
+
+        trait Trait {
+            fn val(&self) -> u32;
+        }
+
+        struct Impl(u32);
+
+        impl Trait for Impl {
+            fn val(&self) -> u32 {
+                self.0
+            }
+        }
+
+        let pool = OwnPool::new();
+
+        let own: Own<dyn Trait, _> = pool.own(Impl(5));
+        assert_eq!(own.val(), 5);
+        drop(own);
+
+        assert_eq!(pool.free_slots(), 1);
+    }
+}
@@ -1,8 +1,15 @@
-use std::{collections::HashMap, sync::OnceLock};
+use std::{
+    collections::HashMap,
+    panic::Location,
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::{Stamp, own::Addr};
+use crate::{RawPointer, Stamp, own::Addr};
 
 static COUNTER: OnceLock<RefCounter> = OnceLock::new();
 
@@ -11,6 +18,8 @@ type Map = HashMap<Addr, Stamp>;
 #[derive(Default)]
 pub(crate) struct RefCounter {
     deallocators: RwLock<Map>,
+    /// Objects being constructed by `Own::new_cyclic`
+    constructing: RwLock<Map>,
 }
 
 impl RefCounter {
@@ -22,15 +31,49 @@ impl RefCounter {
         COUNTER.get_or_init(RefCounter::default).deallocators.write()
     }
 
+    fn constructing() -> &'static RwLock<Map> {
+        &COUNTER.get_or_init(RefCounter::default).constructing
+    }
+
     pub(crate) fn stamp_for_address(addr: Addr) -> Option<Stamp> {
         Self::counter().get(&addr).copied()
     }
 
-    pub(crate) fn add(
-        addr: Addr,
-        #[cfg(feature = "pointers_info")] location: &'static std::panic::Location,
-    ) -> Stamp {
+    pub(crate) fn add(addr: Addr, type_name: &'static str, location: &'static Location<'static>) -> Stamp {
+        let stamp = stamp();
+        Self::register(addr, stamp, type_name, location);
+        stamp
+    }
+
+    /// Issues stamp for object which is not constructed yet.
+    /// Weak pointers with this stamp are null until `commit` is called.
+    pub(crate) fn reserve(addr: Addr) -> Stamp {
         let stamp = stamp();
+        Self::constructing().write().insert(addr, stamp);
+        stamp
+    }
+
+    #[cfg_attr(not(feature = "checks"), allow(dead_code))]
+    pub(crate) fn is_constructing(addr: Addr, stamp: Stamp) -> bool {
+        Self::constructing().read().get(&addr) == Some(&stamp)
+    }
+
+    pub(crate) fn cancel(addr: Addr) {
+        Self::constructing().write().remove(&addr);
+    }
+
+    pub(crate) fn commit(addr: Addr, type_name: &'static str, location: &'static Location<'static>) -> Stamp {
+        let stamp = Self::constructing()
+            .write()
+            .remove(&addr)
+            .expect("Committing object which was not reserved");
+
+        Self::register(addr, stamp, type_name, location);
+
+        stamp
+    }
+
+    fn register(addr: Addr, stamp: Stamp, type_name: &'static str, location: &'static Location<'static>) {
         let existing = Self::counter_mut().insert(addr, stamp);
         if existing.is_some() {
             unreachable!("Adding deallocator of already existing address");
@@ -39,17 +82,39 @@ impl RefCounter {
         #[cfg(feature = "pointers_info")]
         crate::pointers_info::PointerInfo::record_alloc(addr, stamp, location);
 
-        stamp
+        crate::observer::notify_alloc(RawPointer::new(addr, stamp, type_name), location);
     }
 
-    pub(crate) fn remove(addr: Addr, #[cfg(feature = "pointers_info")] backtrace: std::backtrace::Backtrace) {
-        Self::counter_mut().remove(&addr).expect("Removing non existing address");
+    pub(crate) fn remove(
+        addr: Addr,
+        type_name: &'static str,
+        #[cfg(feature = "pointers_info")] backtrace: std::backtrace::Backtrace,
+    ) {
+        let stamp = Self::counter_mut().remove(&addr).expect("Removing non existing address");
+
         #[cfg(feature = "pointers_info")]
-        crate::pointers_info::PointerInfo::record_dealloc(addr, stamp(), backtrace);
+        crate::pointers_info::PointerInfo::record_dealloc(addr, self::stamp(), backtrace);
+
+        crate::observer::notify_free(RawPointer::new(addr, stamp, type_name));
     }
 }
 
+/// Time based stamp bumped to stay unique when several objects are
+/// allocated within the same tick.
 fn stamp() -> Stamp {
+    static LAST: AtomicU64 = AtomicU64::new(0);
+
+    let now = time_stamp();
+    let last = LAST
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap();
+
+    now.max(last + 1)
+}
+
+fn time_stamp() -> Stamp {
     #[cfg(miri)]
     {
         static mut STATIC_START_TIME: Instant =
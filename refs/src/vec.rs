@@ -1,8 +1,192 @@
+use std::{
+    fmt::{Debug, Formatter},
+    ops::{Index, IndexMut},
+};
+
 use crate::{Own, Weak};
 
-pub type OwnVec<T> = Vec<Own<T>>;
 pub type WeakVec<T> = Vec<Weak<T>>;
 
+/// Vector of owned objects addressable by their weak handles.
+pub struct OwnVec<T: ?Sized> {
+    items: Vec<Own<T>>,
+}
+
+impl<T: ?Sized> OwnVec<T> {
+    pub const fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn push(&mut self, own: Own<T>) {
+        self.items.push(own);
+    }
+
+    pub fn push_get_weak(&mut self, own: Own<T>) -> Weak<T> {
+        let weak = own.weak();
+        self.items.push(own);
+        weak
+    }
+
+    pub fn insert(&mut self, index: usize, own: Own<T>) {
+        self.items.insert(index, own);
+    }
+
+    pub fn pop(&mut self) -> Option<Own<T>> {
+        self.items.pop()
+    }
+
+    pub fn remove(&mut self, index: usize) -> Own<T> {
+        self.items.remove(index)
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Own<T>> {
+        self.items.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Own<T>> {
+        self.items.get_mut(index)
+    }
+
+    pub fn weak_at(&self, index: usize) -> Option<Weak<T>> {
+        self.items.get(index).map(Own::weak)
+    }
+
+    pub fn first_weak(&self) -> Option<Weak<T>> {
+        self.items.first().map(Own::weak)
+    }
+
+    pub fn last_weak(&self) -> Option<Weak<T>> {
+        self.items.last().map(Own::weak)
+    }
+
+    pub fn position_of(&self, weak: Weak<T>) -> Option<usize> {
+        if weak.is_null() {
+            return None;
+        }
+        self.items.iter().position(|own| own.identity() == weak.identity())
+    }
+
+    pub fn contains_weak(&self, weak: Weak<T>) -> bool {
+        self.position_of(weak).is_some()
+    }
+
+    /// Preserves order of remaining elements.
+    pub fn remove_by_weak(&mut self, weak: Weak<T>) -> Option<Own<T>> {
+        let index = self.position_of(weak)?;
+        Some(self.items.remove(index))
+    }
+
+    /// Faster than `remove_by_weak` but moves last element into the freed
+    /// slot.
+    pub fn swap_remove_weak(&mut self, weak: Weak<T>) -> Option<Own<T>> {
+        let index = self.position_of(weak)?;
+        Some(self.items.swap_remove(index))
+    }
+
+    pub fn retain_weak(&mut self, mut keep: impl FnMut(Weak<T>) -> bool) {
+        self.items.retain(|own| keep(own.weak()));
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Weak<T>> + ExactSizeIterator + '_ {
+        self.items.iter().map(Own::weak)
+    }
+
+    pub fn iter_own(&self) -> std::slice::Iter<'_, Own<T>> {
+        self.items.iter()
+    }
+
+    pub fn iter_own_mut(&mut self) -> std::slice::IterMut<'_, Own<T>> {
+        self.items.iter_mut()
+    }
+
+    pub fn as_slice(&self) -> &[Own<T>] {
+        &self.items
+    }
+
+    pub fn into_inner(self) -> Vec<Own<T>> {
+        self.items
+    }
+}
+
+impl<T: ?Sized> Default for OwnVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> From<Vec<Own<T>>> for OwnVec<T> {
+    fn from(items: Vec<Own<T>>) -> Self {
+        Self { items }
+    }
+}
+
+impl<T: ?Sized> FromIterator<Own<T>> for OwnVec<T> {
+    fn from_iter<I: IntoIterator<Item = Own<T>>>(iter: I) -> Self {
+        Self {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: ?Sized> Extend<Own<T>> for OwnVec<T> {
+    fn extend<I: IntoIterator<Item = Own<T>>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+impl<T: ?Sized> IntoIterator for OwnVec<T> {
+    type Item = Own<T>;
+    type IntoIter = std::vec::IntoIter<Own<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<T: ?Sized> Index<usize> for OwnVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.items[index]
+    }
+}
+
+impl<T: ?Sized> IndexMut<usize> for OwnVec<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.items[index]
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for OwnVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for OwnVec<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.items.fmt(f)
+    }
+}
+
 pub trait RefsVec<T> {
     fn into_own(self) -> OwnVec<T>;
 }
@@ -28,7 +212,10 @@ mod test {
     use hreads::set_current_thread_as_main;
     use serial_test::serial;
 
-    use crate::vec::{OwnVec, RefsVec};
+    use crate::{
+        Own, Weak,
+        vec::{OwnVec, RefsVec},
+    };
 
     #[test]
     #[serial]
@@ -45,4 +232,69 @@ mod test {
         assert_eq!(owned_vec.pop().unwrap(), 5);
         set_current_thread_as_main();
     }
+
+    #[test]
+    #[serial]
+    fn own_vec_weak_operations() {
+        set_current_thread_as_main();
+
+        let mut vec = OwnVec::new();
+
+        let one = vec.push_get_weak(Own::new(1));
+        let two = vec.push_get_weak(Own::new(2));
+        let three = vec.push_get_weak(Own::new(3));
+        let four = vec.push_get_weak(Own::new(4));
+
+        assert_eq!(vec.position_of(three), Some(2));
+        assert_eq!(vec.position_of(Weak::default()), None);
+        assert_eq!(vec.iter().map(|weak| *weak).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        let removed = vec.remove_by_weak(two).unwrap();
+        assert_eq!(removed, 2);
+        assert!(two.is_ok());
+        drop(removed);
+        assert!(two.is_null());
+        assert_eq!(vec.remove_by_weak(two), None);
+
+        assert!(vec.swap_remove_weak(one).is_some());
+        assert!(one.is_null());
+        assert_eq!(vec.iter().map(|weak| *weak).collect::<Vec<_>>(), vec![4, 3]);
+
+        vec[0] = 40;
+        assert_eq!(*four, 40);
+
+        vec.retain_weak(|weak| weak != three);
+        assert!(three.is_null());
+        assert_eq!(vec.len(), 1);
+        assert!(vec.contains_weak(four));
+        assert_eq!(vec.last_weak(), Some(four));
+    }
+
+    #[test]
+    #[serial]
+    fn own_vec_unsized() {
+        set_current_thread_as_main();
+
+        trait Trait {
+            fn val(&self) -> i32;
+        }
+
+        struct Impl(i32);
+
+        impl Trait for Impl {
+            fn val(&self) -> i32 {
+                self.0
+            }
+        }
+
+        let mut vec = OwnVec::<dyn Trait>::default();
+
+        let five = vec.push_get_weak(Own::new(Impl(5)));
+        vec.push(Own::new(Impl(10)));
+
+        assert_eq!(vec.iter().map(|weak| weak.val()).sum::<i32>(), 15);
+        assert_eq!(vec.position_of(five), Some(0));
+        assert_eq!(vec.remove_by_weak(five).unwrap().val(), 5);
+        assert_eq!(vec[0].val(), 10);
+    }
 }
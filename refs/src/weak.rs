@@ -8,7 +8,12 @@ use std::{
     ptr::{from_ref, null, null_mut},
 };
 
-use crate::{AsAny, Erased, RawPointer, Rglica, ToRglica, ref_counter::RefCounter, weak_from_ref};
+use crate::{
+    AsAny, Erased, RawPointer, Rglica, ToRglica,
+    own::{Addr, Stamp},
+    ref_counter::RefCounter,
+    weak_from_ref,
+};
 
 pub(crate) const PTR_SIZE: usize = size_of::<usize>();
 
@@ -67,6 +72,10 @@ impl<T: ?Sized> Weak<T> {
         RawPointer::new(self.addr(), self.stamp, self.type_name)
     }
 
+    pub(crate) fn identity(&self) -> (Addr, Stamp) {
+        (self.addr(), self.stamp)
+    }
+
     pub unsafe fn from_raw(ptr: RawPointer) -> Self {
         let mut new = Weak::<T> {
             stamp: ptr.stamp(),
@@ -167,6 +176,15 @@ impl<T: ?Sized> Weak<T> {
         }
 
         if self.is_null() {
+            if RefCounter::is_constructing(self.addr(), self.stamp) {
+                let message = format!(
+                    "Defererencing weak pointer during Own::new_cyclic construction: {}",
+                    self.type_name
+                );
+                error!("{message}");
+                panic!("{message}");
+            }
+
             #[cfg(feature = "pointers_info")]
             let message = format!(
                 "Defererencing already freed weak pointer: {}. \nInfo: {}",
@@ -207,10 +225,11 @@ impl<T> Weak<T> {
             "Invalid address. In could be a closure or empty type."
         );
 
-        #[cfg(feature = "pointers_info")]
-        let stamp = RefCounter::add(address, std::panic::Location::caller());
-        #[cfg(not(feature = "pointers_info"))]
-        let stamp = RefCounter::add(address);
+        let stamp = RefCounter::add(
+            address,
+            std::any::type_name::<T>(),
+            std::panic::Location::caller(),
+        );
 
         Self {
             ptr,
@@ -0,0 +1,141 @@
+use crate::Weak;
+
+/// Closures bound to a weak target. Bound closure calls `f` only while the
+/// target is alive, otherwise it does nothing and returns `R::default()`.
+/// Wrap in `Box` to store as `Box<dyn FnMut(..)>`.
+impl<T: ?Sized> Weak<T> {
+    pub fn bind<R: Default>(self, mut f: impl FnMut(&mut T) -> R) -> impl FnMut() -> R {
+        let mut weak = self;
+        move || weak.get_mut().map(&mut f).unwrap_or_default()
+    }
+
+    pub fn bind1<A, R: Default>(self, mut f: impl FnMut(&mut T, A) -> R) -> impl FnMut(A) -> R {
+        let mut weak = self;
+        move |a| weak.get_mut().map(|this| f(this, a)).unwrap_or_default()
+    }
+
+    pub fn bind2<A, B, R: Default>(self, mut f: impl FnMut(&mut T, A, B) -> R) -> impl FnMut(A, B) -> R {
+        let mut weak = self;
+        move |a, b| weak.get_mut().map(|this| f(this, a, b)).unwrap_or_default()
+    }
+
+    pub fn bind3<A, B, C, R: Default>(
+        self,
+        mut f: impl FnMut(&mut T, A, B, C) -> R,
+    ) -> impl FnMut(A, B, C) -> R {
+        let mut weak = self;
+        move |a, b, c| weak.get_mut().map(|this| f(this, a, b, c)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hreads::set_current_thread_as_main;
+    use serial_test::serial;
+
+    use crate::{Own, Weak};
+
+    #[derive(Default)]
+    struct Counter {
+        sum: i32,
+    }
+
+    impl Counter {
+        fn add(&mut self, val: i32) -> i32 {
+            self.sum += val;
+            self.sum
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn bind_arities() {
+        set_current_thread_as_main();
+
+        let counter = Own::new(Counter::default());
+        let weak = counter.weak();
+
+        let mut zero = weak.bind(|this| this.add(1));
+        let mut one = weak.bind1(|this, a: i32| this.add(a));
+        let mut two = weak.bind2(|this, a: i32, b: i32| this.add(a + b));
+        let mut three = weak.bind3(|this, a: i32, b: i32, c: i32| this.add(a + b + c));
+
+        assert_eq!(zero(), 1);
+        assert_eq!(one(2), 3);
+        assert_eq!(two(3, 4), 10);
+        assert_eq!(three(5, 6, 7), 28);
+        assert_eq!(counter.sum, 28);
+
+        drop(counter);
+
+        assert_eq!(zero(), 0);
+        assert_eq!(one(2), 0);
+        assert_eq!(two(3, 4), 0);
+        assert_eq!(three(5, 6, 7), 0);
+    }
+
+    #[test]
+    #[serial]
+    fn bind_skips_when_freed() {
+        set_current_thread_as_main();
+
+        let counter = Own::new(Counter::default());
+
+        let mut unit = counter.weak().bind1(|this, a: i32| {
+            this.add(a);
+        });
+        let mut string = counter.weak().bind(|this| this.sum.to_string());
+        let mut option = counter.weak().bind(|this| Some(this.sum));
+
+        unit(5);
+        assert_eq!(string(), "5");
+        assert_eq!(option(), Some(5));
+
+        drop(counter);
+
+        unit(5);
+        assert_eq!(string(), "");
+        assert_eq!(option(), None);
+
+        let mut null = Weak::<Counter>::default().bind(|this| this.add(1));
+        assert_eq!(null(), 0);
+    }
+
+    #[test]
+    #[serial]
+    fn bind_boxed() {
+        set_current_thread_as_main();
+
+        trait Handler {
+            fn handle(&mut self, val: i32);
+        }
+
+        impl Handler for Counter {
+            fn handle(&mut self, val: i32) {
+                self.add(val);
+            }
+        }
+
+        let counter = Own::new(Counter::default());
+        let handler: Weak<dyn Handler> = counter.weak();
+
+        let mut callbacks: Vec<Box<dyn FnMut(i32)>> = vec![
+            Box::new(handler.bind1(|this, val| this.handle(val))),
+            Box::new(counter.weak().bind1(|this, val| {
+                this.add(val * 10);
+            })),
+        ];
+
+        for callback in &mut callbacks {
+            callback(1);
+        }
+
+        assert_eq!(counter.sum, 11);
+
+        drop(counter);
+
+        for callback in &mut callbacks {
+            callback(1);
+        }
+    }
+}
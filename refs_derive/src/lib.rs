@@ -0,0 +1,104 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident, LitStr, parse_macro_input};
+
+/// Generates `edit_<field>` methods returning `refs::editor::Editor` for
+/// each named field and `FIELD_<FIELD>` constants with its `FieldId`.
+/// Fields marked with `#[edit(skip)]` are ignored.
+/// Editors of fields marked with `#[edit(rollback)]` are created with
+/// `Editor::with_rollback`.
+#[proc_macro_derive(Edit, attributes(edit))]
+pub fn derive_edit(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let Data::Struct(data) = &input.data else {
+        return syn::Error::new_spanned(&input.ident, "Edit can be derived only for structs")
+            .to_compile_error()
+            .into();
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return syn::Error::new_spanned(
+            &input.ident,
+            "Edit can be derived only for structs with named fields",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let mut items = vec![];
+
+    for field in &fields.named {
+        let attrs = match EditAttrs::parse(field) {
+            Ok(attrs) => attrs,
+            Err(err) => return err.to_compile_error().into(),
+        };
+
+        if attrs.skip {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().expect("Named field without ident");
+        let name = ident.to_string();
+        let name = name.strip_prefix("r#").unwrap_or(&name);
+        let vis = &field.vis;
+        let ty = &field.ty;
+
+        let method = format_ident!("edit_{}", name);
+        let id_const = Ident::new(&format!("FIELD_{}", name.to_uppercase()), Span::call_site());
+        let name = LitStr::new(name, Span::call_site());
+
+        let rollback = attrs.rollback.then(|| quote! { .with_rollback() });
+
+        items.push(quote! {
+            #vis const #id_const: ::refs::editor::FieldId = ::refs::editor::FieldId::new(#name);
+
+            #vis fn #method(&mut self) -> ::refs::editor::Editor<'_, Self, #ty> {
+                ::refs::editor::Editor::for_field(self, |obj| &mut obj.#ident, Self::#id_const)#rollback
+            }
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#items)*
+        }
+    }
+    .into()
+}
+
+#[derive(Default)]
+struct EditAttrs {
+    skip:     bool,
+    rollback: bool,
+}
+
+impl EditAttrs {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("edit") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rollback") {
+                    attrs.rollback = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("Unsupported edit attribute. Expected `skip` or `rollback`"))
+                }
+            })?;
+        }
+
+        Ok(attrs)
+    }
+}
@@ -1,8 +1,10 @@
 #![allow(internal_features)]
 #![allow(clippy::vec_box)]
 #![feature(core_intrinsics)]
+#![feature(allocator_api)]
 
 use std::{
+    alloc::Allocator,
     intrinsics::black_box,
     io::Error,
     path::Path,
@@ -32,7 +34,7 @@ fn generate_own() -> Vec<Own<u32>> {
     (0..50_000).map(|_| Own::new((0..5).fake())).collect()
 }
 
-fn calculate_own_sum(data: &Vec<Own<u32>>) -> u32 {
+fn calculate_own_sum<A: Allocator>(data: &Vec<Own<u32, A>>) -> u32 {
     let mut sum = 0;
     for val in data {
         sum += **val;
@@ -56,6 +58,45 @@ fn calculate_weak_sum(data: &Vec<Weak<u32>>) -> u32 {
     sum
 }
 
+#[cfg(feature = "bench")]
+fn bench_own_new(frames: usize) -> u64 {
+    let mut sum = 0;
+    for _ in 0..frames {
+        let nodes: Vec<Own<u32>> = (0..50_000).map(Own::new).collect();
+        sum += u64::from(calculate_own_sum(black_box(&nodes)));
+    }
+    sum
+}
+
+#[cfg(feature = "bench")]
+fn bench_own_pool(frames: usize) -> u64 {
+    let pool = refs::OwnPool::with_capacity(50_000);
+    let mut sum = 0;
+    for _ in 0..frames {
+        let nodes: Vec<_> = (0..50_000).map(|val| pool.own(val)).collect();
+        sum += u64::from(calculate_own_sum(black_box(&nodes)));
+    }
+    sum
+}
+
+/// Compares `Own::new` with `OwnPool`. Run with `make bench`.
+#[cfg(feature = "bench")]
+fn bench_pool() {
+    for _ in 0..4 {
+        let start_own_new = Instant::now();
+        let sum = bench_own_new(black_box(10));
+        dbg!(sum);
+        dbg!(start_own_new.elapsed());
+    }
+
+    for _ in 0..4 {
+        let start_own_pool = Instant::now();
+        let sum = bench_own_pool(black_box(10));
+        dbg!(sum);
+        dbg!(start_own_pool.elapsed());
+    }
+}
+
 fn main() -> Result<()> {
     let start = Instant::now();
     let data_own = generate_own();
@@ -93,6 +134,9 @@ fn main() -> Result<()> {
 
     set_current_thread_as_main();
 
+    #[cfg(feature = "bench")]
+    bench_pool();
+
     Data::set_root_path("a");
 
     let data = Data::get("a");